@@ -0,0 +1,35 @@
+use json::JsonValue;
+
+/// Index into `Economy::commodities`.
+pub type CommodityId = usize;
+
+/// Something that can be bought and sold, loaded from the commodity catalogue.
+#[derive(Clone, Debug)]
+pub struct Commodity {
+    pub id: String,
+    pub name: String,
+    /// Per unit, where supply meets demand.
+    pub base_price: f32,
+    /// How much its demand wanders on its own, from 0 for staples upwards.
+    pub volatility: f32,
+    /// Per unit, of cargo hold space.
+    pub volume: f32,
+    /// Per unit, added to the ship carrying it.
+    pub mass: f32,
+}
+
+impl Commodity {
+    pub fn from_json(value: &JsonValue) -> Option<Commodity> {
+        let id = value["id"].as_str()?.to_string();
+        let name = value["name"].as_str().unwrap_or(&id).to_string();
+
+        Some(Commodity {
+            id,
+            name,
+            base_price: value["base_price"].as_f32()?,
+            volatility: value["volatility"].as_f32().unwrap_or(0.0),
+            volume: value["volume"].as_f32().unwrap_or(1.0),
+            mass: value["mass"].as_f32().unwrap_or(0.0),
+        })
+    }
+}
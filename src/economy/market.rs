@@ -0,0 +1,81 @@
+use json::JsonValue;
+
+use super::commodity::{Commodity, CommodityId};
+
+/// Index into `Economy::markets`.
+pub type MarketId = usize;
+
+const DEFAULT_TURNOVER: f32 = 0.02;
+
+/// How much of one commodity a market has and wants.
+#[derive(Clone, Copy, Debug)]
+pub struct Stock {
+    pub supply: f32,
+    pub demand: f32,
+    /// What local industry keeps supply and demand at, without any trading.
+    pub baseline_supply: f32,
+    pub baseline_demand: f32,
+    /// Fraction of the gap to the baseline local industry closes per second.
+    pub turnover: f32,
+}
+
+impl Stock {
+    fn from_json(value: &JsonValue) -> Option<Stock> {
+        let supply = value["supply"].as_f32()?;
+        let demand = value["demand"].as_f32()?;
+
+        Some(Stock {
+            supply,
+            demand,
+            baseline_supply: supply,
+            baseline_demand: demand,
+            turnover: value["turnover"].as_f32().unwrap_or(DEFAULT_TURNOVER),
+        })
+    }
+}
+
+/// The commodity exchange of one station.
+#[derive(Clone, Debug)]
+pub struct Market {
+    pub id: String,
+    pub name: String,
+    /// Per commodity, `None` for what isn't traded here.
+    pub stocks: Vec<Option<Stock>>,
+}
+
+impl Market {
+    pub fn from_json(value: &JsonValue, commodities: &[Commodity]) -> Option<Market> {
+        let id = value["id"].as_str()?.to_string();
+        let name = value["name"].as_str().unwrap_or(&id).to_string();
+
+        let stock = &value["stock"];
+        stock
+            .entries()
+            .filter(|(key, _)| !commodities.iter().any(|v| v.id == *key))
+            .for_each(|(key, _)| log::warn!("{} trades unknown commodity {}", id, key));
+
+        let stocks = commodities
+            .iter()
+            .map(|v| Stock::from_json(&stock[v.id.as_str()]))
+            .collect();
+
+        Some(Market { id, name, stocks })
+    }
+
+    /// Every commodity traded here.
+    pub fn traded(&self) -> impl Iterator<Item = CommodityId> + '_ {
+        self.stocks
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.is_some())
+            .map(|v| v.0)
+    }
+
+    pub fn stock(&self, commodity: CommodityId) -> Option<&Stock> {
+        self.stocks.get(commodity)?.as_ref()
+    }
+
+    pub fn stock_mut(&mut self, commodity: CommodityId) -> Option<&mut Stock> {
+        self.stocks.get_mut(commodity)?.as_mut()
+    }
+}
@@ -0,0 +1,3 @@
+pub mod commodity;
+pub mod market;
+pub mod simulation;
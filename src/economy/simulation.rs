@@ -0,0 +1,390 @@
+use crate::util::loader::{load_text, parse_list};
+
+use super::{
+    commodity::{Commodity, CommodityId},
+    market::{Market, MarketId},
+};
+
+/// How strongly the demand to supply ratio moves the price.
+const ELASTICITY: f32 = 0.5;
+/// Prices stay within these multiples of the base price.
+const MIN_PRICE_FACTOR: f32 = 0.25;
+const MAX_PRICE_FACTOR: f32 = 4.0;
+/// Stations buy below and sell above the going rate by this fraction.
+const SPREAD: f32 = 0.08;
+/// Demand picked up per unit bought, and dropped per unit sold.
+const DEMAND_PER_UNIT: f32 = 0.3;
+/// Fraction of the gap to the baseline demand closes per second.
+const DEMAND_RECOVERY: f32 = 0.01;
+/// Scales the random wander of demand by volatility, per square root of a
+/// second so it wanders as far at any frame rate.
+const DEMAND_DRIFT: f32 = 0.05;
+/// Demand never drops below this, its drift is relative and would stall at
+/// zero.
+const MIN_DEMAND: f32 = 1.0;
+
+/// A commodity worth hauling from one market to another.
+#[derive(Clone, Copy, Debug)]
+pub struct Route {
+    pub from: MarketId,
+    pub to: MarketId,
+    pub commodity: CommodityId,
+    /// Per unit, what `to` pays over what `from` charges.
+    pub margin: f32,
+}
+
+/// Every market and what they trade. Knows nothing about rendering or the
+/// world, so it can be ticked on its own.
+#[derive(Clone, Debug)]
+pub struct Economy {
+    pub commodities: Vec<Commodity>,
+    pub markets: Vec<Market>,
+}
+
+impl Economy {
+    /// From the JSON of the commodity catalogue and the markets.
+    pub fn parse(commodities: &str, markets: &str) -> Option<Economy> {
+        let commodities = parse_list(commodities, "commodity", Commodity::from_json)?;
+        let markets = parse_list(markets, "market", |v| Market::from_json(v, &commodities))?;
+
+        Some(Economy {
+            commodities,
+            markets,
+        })
+    }
+
+    pub fn market_id(&self, id: &str) -> Option<MarketId> {
+        self.markets.iter().position(|v| v.id == id)
+    }
+
+    /// The going rate for one unit at `supply`.
+    fn unit_price(&self, commodity: CommodityId, supply: f32, demand: f32) -> f32 {
+        let base_price = self.commodities[commodity].base_price;
+        let factor = (demand.max(1.0) / supply.max(1.0))
+            .powf(ELASTICITY)
+            .clamp(MIN_PRICE_FACTOR, MAX_PRICE_FACTOR);
+
+        base_price * factor
+    }
+
+    /// What buying `amount` units costs, every unit a bit more than the last
+    /// as supply runs low. `None` if the market doesn't have that many.
+    pub fn buy_price(&self, market: MarketId, commodity: CommodityId, amount: u32) -> Option<f32> {
+        self.buy_price_spread(market, commodity, amount, 1.0)
+    }
+
+    /// `buy_price` with `spread` times the usual spread.
+    pub fn buy_price_spread(
+        &self,
+        market: MarketId,
+        commodity: CommodityId,
+        amount: u32,
+        spread: f32,
+    ) -> Option<f32> {
+        let stock = self.markets.get(market)?.stock(commodity)?;
+
+        if stock.supply < amount as f32 {
+            return None;
+        }
+
+        let total = (0..amount)
+            .map(|i| self.unit_price(commodity, stock.supply - i as f32, stock.demand))
+            .sum::<f32>();
+
+        Some(total * (1.0 + SPREAD * spread))
+    }
+
+    /// What selling `amount` units gets, every unit a bit less than the last
+    /// as supply piles up. `None` if the market doesn't trade it. Every unit
+    /// goes at the supply it makes, so selling back what was just bought
+    /// never pays more than it cost.
+    pub fn sell_price(&self, market: MarketId, commodity: CommodityId, amount: u32) -> Option<f32> {
+        self.sell_price_spread(market, commodity, amount, 1.0)
+    }
+
+    /// `sell_price` with `spread` times the usual spread.
+    pub fn sell_price_spread(
+        &self,
+        market: MarketId,
+        commodity: CommodityId,
+        amount: u32,
+        spread: f32,
+    ) -> Option<f32> {
+        let stock = self.markets.get(market)?.stock(commodity)?;
+
+        let total = (0..amount)
+            .map(|i| self.unit_price(commodity, stock.supply + (i + 1) as f32, stock.demand))
+            .sum::<f32>();
+
+        Some(total * (1.0 - SPREAD * spread))
+    }
+
+    /// Every route that turns a profit on `amount` units, best first.
+    pub fn routes(&self, amount: u32) -> Vec<Route> {
+        let mut routes = vec![];
+
+        for from in 0..self.markets.len() {
+            for commodity in self.markets[from].traded() {
+                let cost = match self.buy_price(from, commodity, amount) {
+                    Some(v) => v,
+                    None => continue,
+                };
+
+                for to in (0..self.markets.len()).filter(|v| *v != from) {
+                    let margin = match self.sell_price(to, commodity, amount) {
+                        Some(v) => (v - cost) / amount as f32,
+                        None => continue,
+                    };
+
+                    if margin > 0.0 {
+                        routes.push(Route {
+                            from,
+                            to,
+                            commodity,
+                            margin,
+                        });
+                    }
+                }
+            }
+        }
+
+        routes.sort_by(|a, b| b.margin.total_cmp(&a.margin));
+
+        routes
+    }
+
+    /// Moves `amount` units out of the market, or into it if negative.
+    /// Buying also drives up demand, selling brings it down.
+    pub fn trade(&mut self, market: MarketId, commodity: CommodityId, amount: f32) -> Option<()> {
+        let stock = self.markets.get_mut(market)?.stock_mut(commodity)?;
+
+        stock.supply = (stock.supply - amount).max(0.0);
+        stock.demand = (stock.demand + amount * DEMAND_PER_UNIT).max(MIN_DEMAND);
+
+        Some(())
+    }
+
+    /// Local industry works supply back to its baseline, demand recovers
+    /// and wanders by the commodity's volatility. `noise` gives a value
+    /// between -1 and 1 for every stock, e.g. `rand::gen_range(-1.0, 1.0)`.
+    pub fn tick(&mut self, dt: f32, mut noise: impl FnMut() -> f32) {
+        for market in self.markets.iter_mut() {
+            for (commodity, stock) in self.commodities.iter().zip(market.stocks.iter_mut()) {
+                let stock = match stock {
+                    Some(v) => v,
+                    None => continue,
+                };
+
+                let industry = (stock.baseline_supply - stock.supply) * stock.turnover * dt;
+                stock.supply = (stock.supply + industry).max(0.0);
+
+                let recovery = (stock.baseline_demand - stock.demand) * DEMAND_RECOVERY * dt;
+                let drift = stock.demand * commodity.volatility * DEMAND_DRIFT * noise();
+
+                stock.demand = (stock.demand + recovery + drift * dt.sqrt()).max(MIN_DEMAND);
+            }
+        }
+    }
+}
+
+pub async fn load_economy(commodities: &str, markets: &str) -> Economy {
+    log::info!("loading economy at {} and {}", commodities, markets);
+
+    let commodities = load_text(commodities).await;
+    let markets = load_text(markets).await;
+
+    Economy::parse(&commodities, &markets).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMODITIES: &str = r#"[
+        {"id": "ore", "base_price": 20, "volatility": 0.1},
+        {"id": "fuel", "base_price": 45, "volatility": 0.15}
+    ]"#;
+    const MARKETS: &str = r#"[
+        {"id": "mine", "stock": {"ore": {"supply": 400, "demand": 80}}},
+        {"id": "depot", "stock": {
+            "ore": {"supply": 20, "demand": 150},
+            "fuel": {"supply": 100, "demand": 100, "turnover": 0.1}
+        }}
+    ]"#;
+
+    fn economy() -> Economy {
+        Economy::parse(COMMODITIES, MARKETS).unwrap()
+    }
+
+    /// Same sequence for the same seed, spread over -1 to 1.
+    fn seeded(seed: u64) -> impl FnMut() -> f32 {
+        let mut state = seed;
+        move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+        }
+    }
+
+    #[test]
+    fn parses_markets() {
+        let economy = economy();
+
+        assert_eq!(economy.markets[1].id, "depot");
+        assert!(economy.markets[0].stock(1).is_none());
+        assert_eq!(economy.markets[1].stocks.iter().flatten().count(), 2);
+    }
+
+    #[test]
+    fn buying_more_costs_more_per_unit() {
+        let economy = economy();
+
+        let prices = (1..=10)
+            .map(|v| economy.buy_price(1, 0, v).unwrap() / v as f32)
+            .collect::<Vec<_>>();
+
+        assert!(prices.windows(2).all(|v| v[0] < v[1]));
+        assert!(economy.buy_price(1, 0, 21).is_none());
+    }
+
+    #[test]
+    fn selling_more_pays_less_per_unit() {
+        let economy = economy();
+
+        let prices = (1..=10)
+            .map(|v| economy.sell_price(1, 0, v).unwrap() / v as f32)
+            .collect::<Vec<_>>();
+
+        assert!(prices.windows(2).all(|v| v[0] > v[1]));
+        assert!(economy.sell_price(0, 1, 1).is_none());
+    }
+
+    #[test]
+    fn buying_back_loses_the_spread() {
+        let economy = economy();
+
+        assert!(economy.sell_price(1, 1, 1).unwrap() < economy.buy_price(1, 1, 1).unwrap());
+    }
+
+    #[test]
+    fn round_trips_lose_money() {
+        let mut economy = economy();
+        economy.markets[1].stock_mut(0).unwrap().supply = 3.0;
+
+        let cost = economy.buy_price(1, 0, 1).unwrap();
+        economy.trade(1, 0, 1.0);
+        let gain = economy.sell_price(1, 0, 1).unwrap();
+
+        assert!(gain < cost, "{} for {}", gain, cost);
+    }
+
+    #[test]
+    fn scarce_goods_cost_more() {
+        let economy = economy();
+
+        assert!(economy.buy_price(1, 0, 1).unwrap() > economy.buy_price(0, 0, 1).unwrap());
+    }
+
+    #[test]
+    fn trade_moves_supply_and_demand() {
+        let mut economy = economy();
+        let before = *economy.markets[1].stock(1).unwrap();
+
+        economy.trade(1, 1, 10.0);
+        let bought = *economy.markets[1].stock(1).unwrap();
+        assert_eq!(bought.supply, before.supply - 10.0);
+        assert!(bought.demand > before.demand);
+
+        economy.trade(1, 1, -20.0);
+        let sold = *economy.markets[1].stock(1).unwrap();
+        assert_eq!(sold.supply, before.supply + 10.0);
+        assert!(sold.demand < before.demand);
+
+        economy.trade(1, 1, 1000.0);
+        assert_eq!(economy.markets[1].stock(1).unwrap().supply, 0.0);
+        assert!(economy.trade(0, 1, 1.0).is_none());
+    }
+
+    #[test]
+    fn trade_and_tick_share_the_demand_floor() {
+        let mut economy = economy();
+
+        economy.trade(1, 1, -10000.0);
+        assert_eq!(economy.markets[1].stock(1).unwrap().demand, MIN_DEMAND);
+
+        economy.tick(1.0, || -1.0);
+        assert!(economy.markets[1].stock(1).unwrap().demand >= MIN_DEMAND);
+    }
+
+    #[test]
+    fn tick_returns_to_baseline() {
+        let mut economy = economy();
+        economy.trade(1, 1, 80.0);
+
+        for _ in 0..60 * 600 {
+            economy.tick(1.0 / 60.0, || 0.0);
+        }
+
+        let stock = economy.markets[1].stock(1).unwrap();
+        assert!((stock.supply - stock.baseline_supply).abs() < 1.0);
+        assert!((stock.demand - stock.baseline_demand).abs() < 1.0);
+    }
+
+    #[test]
+    fn industry_leaves_demand_alone() {
+        let mut economy = economy();
+        economy.trade(1, 1, 50.0);
+        let before = *economy.markets[1].stock(1).unwrap();
+
+        // no time for demand to recover, only supply moves
+        economy.markets[1].stock_mut(1).unwrap().baseline_demand = before.demand;
+        economy.tick(1.0, || 0.0);
+
+        let after = economy.markets[1].stock(1).unwrap();
+        assert!(after.supply > before.supply);
+        assert_eq!(after.demand, before.demand);
+    }
+
+    #[test]
+    fn tick_is_reproducible_and_stays_near_baseline() {
+        let mut a = economy();
+        let mut b = economy();
+        let (mut noise_a, mut noise_b) = (seeded(7), seeded(7));
+
+        for _ in 0..60 * 600 {
+            a.tick(1.0 / 60.0, &mut noise_a);
+            b.tick(1.0 / 60.0, &mut noise_b);
+        }
+
+        let (stock_a, stock_b) = (
+            a.markets[1].stock(0).unwrap(),
+            b.markets[1].stock(0).unwrap(),
+        );
+        assert_eq!(stock_a.demand, stock_b.demand);
+        assert!((stock_a.demand / stock_a.baseline_demand - 1.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn drift_does_not_depend_on_the_frame_rate() {
+        let spread = |fps: u32| {
+            let runs = 200;
+            let total = (0..runs)
+                .map(|seed| {
+                    let mut economy = economy();
+                    let mut noise = seeded(seed);
+                    for _ in 0..fps * 10 {
+                        economy.tick(1.0 / fps as f32, &mut noise);
+                    }
+                    let stock = economy.markets[1].stock(0).unwrap();
+                    (stock.demand - stock.baseline_demand).powi(2)
+                })
+                .sum::<f32>();
+
+            (total / runs as f32).sqrt()
+        };
+
+        let (slow, fast) = (spread(30), spread(240));
+        assert!((slow / fast - 1.0).abs() < 0.3, "{} vs {}", slow, fast);
+    }
+}
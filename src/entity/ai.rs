@@ -0,0 +1,136 @@
+use nalgebra::{Complex, Unit};
+use rapier2d::{
+    math::{Point, Real, Vector},
+    prelude::RigidBody,
+};
+
+use crate::util::screen::wrap_delta;
+
+use super::{
+    entity::Entity,
+    thrusters::{Thrust, ThrustersLike},
+};
+
+/// Escorts stop thrusting once they are this close to their leader.
+const FOLLOW_DISTANCE: Real = 250.0;
+/// Holding station, escorts brake with full reverse thrust from this speed
+/// along their heading on, and with less below it.
+const STATION_BRAKE_SPEED: Real = 100.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ai {
+    /// Distance at which it starts shooting.
+    pub range: Real,
+    /// Half angle in front of the ship the target has to be in to shoot.
+    pub fire_cone: Real,
+    pub turn_rate: Real,
+    /// Flies into the target instead of keeping its distance.
+    pub ram: bool,
+    /// Hit by the player or their fleet, it fights back whatever the
+    /// reputation.
+    pub provoked: bool,
+}
+
+impl Default for Ai {
+    fn default() -> Self {
+        Self {
+            range: 900.0,
+            fire_cone: 0.15,
+            turn_rate: 2.0,
+            ram: false,
+            provoked: false,
+        }
+    }
+}
+
+impl Ai {
+    pub fn escort() -> Self {
+        Self::default()
+    }
+
+    /// Hauls cargo, slow and unarmed.
+    pub fn merchant() -> Self {
+        Self {
+            turn_rate: 1.5,
+            ..Default::default()
+        }
+    }
+
+    pub fn rammer() -> Self {
+        Self {
+            turn_rate: 3.0,
+            ram: true,
+            ..Default::default()
+        }
+    }
+}
+
+pub trait AiLike {
+    /// Turns toward `target`, and rammers thrust at it. Returns whether the
+    /// weapons should fire.
+    fn update_ai(
+        &mut self,
+        target: Point<Real>,
+        rigid_body: &mut RigidBody,
+        dt: f32,
+    ) -> Option<bool>;
+
+    /// Flies toward `leader` and holds station next to it.
+    fn follow(&mut self, leader: Point<Real>, rigid_body: &mut RigidBody, dt: f32) -> Option<()>;
+}
+
+/// Turns the ship toward `target`, returns the delta to it and the angle left.
+fn turn_toward(ai: &Ai, target: Point<Real>, rigid_body: &mut RigidBody) -> (Vector<Real>, Real) {
+    let pos = rigid_body.translation();
+    let delta = wrap_delta(Point::new(pos.x, pos.y), target);
+    let direction = Unit::from_complex(Complex::new(delta.x, delta.y));
+    let angle = rigid_body.rotation().angle_to(&direction);
+
+    rigid_body.set_angvel(angle * ai.turn_rate, true);
+
+    (delta, angle)
+}
+
+impl AiLike for Entity {
+    fn update_ai(
+        &mut self,
+        target: Point<Real>,
+        rigid_body: &mut RigidBody,
+        dt: f32,
+    ) -> Option<bool> {
+        let ai = self.ai?;
+        let (delta, angle) = turn_toward(&ai, target, rigid_body);
+
+        let thrust = Thrust {
+            forward: if ai.ram && angle.abs() < ai.fire_cone * 2.0 {
+                1.0
+            } else {
+                0.0
+            },
+            ..Default::default()
+        };
+        self.apply_thrust(thrust, rigid_body, dt);
+
+        Some(delta.norm() < ai.range && angle.abs() < ai.fire_cone)
+    }
+
+    fn follow(&mut self, leader: Point<Real>, rigid_body: &mut RigidBody, dt: f32) -> Option<()> {
+        let ai = self.ai?;
+        let (delta, _) = turn_toward(&ai, leader, rigid_body);
+
+        let forward = if delta.norm() > FOLLOW_DISTANCE {
+            1.0
+        } else {
+            let heading: Vector<Real> = rigid_body.rotation() * Vector::x();
+            -(rigid_body.linvel().dot(&heading) / STATION_BRAKE_SPEED).clamp(-1.0, 1.0)
+        };
+
+        let thrust = Thrust {
+            forward,
+            ..Default::default()
+        };
+        self.apply_thrust(thrust, rigid_body, dt)?;
+
+        Some(())
+    }
+}
@@ -0,0 +1,70 @@
+use macroquad::models::Mesh;
+
+use super::entity::Entity;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationState {
+    Idle,
+    Thrusting,
+    Damaged,
+}
+
+impl AnimationState {
+    pub fn clip_name(&self) -> &'static str {
+        match self {
+            AnimationState::Idle => "idle",
+            AnimationState::Thrusting => "thrusting",
+            AnimationState::Damaged => "damaged",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Animation {
+    pub state: AnimationState,
+    pub started: f64,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self {
+            state: AnimationState::Idle,
+            started: 0.0,
+        }
+    }
+}
+
+pub trait AnimationLike {
+    /// Switches to the clip for `state`, restarting it only if it changed.
+    fn set_animation_state(&mut self, state: AnimationState, current_time: f64) -> Option<()>;
+
+    /// The frame to draw over the base mesh right now, if any.
+    fn animation_frame(&self, current_time: f64) -> Option<&'static Mesh>;
+}
+
+impl AnimationLike for Entity {
+    fn set_animation_state(&mut self, state: AnimationState, current_time: f64) -> Option<()> {
+        let animation = self.animation.as_mut()?;
+
+        if animation.state != state {
+            animation.state = state;
+            animation.started = current_time;
+        }
+
+        Some(())
+    }
+
+    fn animation_frame(&self, current_time: f64) -> Option<&'static Mesh> {
+        let animation = self.animation.as_ref()?;
+        let animations = &self.drawable.as_ref()?.resource.animations;
+
+        // resources don't have to provide every state, fall back to idle
+        let clip = animations
+            .get(animation.state.clip_name())
+            .or_else(|| animations.get(AnimationState::Idle.clip_name()))?;
+
+        let frame = clip.frame_at(current_time - animation.started)?;
+
+        Some(&frame.mesh)
+    }
+}
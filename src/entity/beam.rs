@@ -0,0 +1,80 @@
+use rapier2d::{
+    math::{Isometry, Real},
+    prelude::RigidBodySet,
+};
+
+use crate::util::draw::{self, Modulation};
+
+use super::{
+    entity::{Entity, EntityHolder},
+    physics::PhysicsLike,
+};
+
+/// A continuous weapon that hits the first thing along a ray every tick,
+/// instead of spawning projectiles.
+#[derive(Debug, Clone, Copy)]
+pub struct Beam {
+    pub range: Real,
+    pub damage_per_second: f32,
+    /// Damage multiplier reached after `ramp_time` seconds on the same target.
+    pub max_ramp: f32,
+    pub ramp_time: f64,
+}
+
+impl Beam {
+    pub fn damage_multiplier(&self, time_on_target: f64) -> f32 {
+        let t = (time_on_target / self.ramp_time).clamp(0.0, 1.0) as f32;
+
+        1.0 + (self.max_ramp - 1.0) * t
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BeamState {
+    pub firing: bool,
+    /// Up to the first hit, or the full range.
+    pub length: Real,
+    pub target: Option<EntityHolder>,
+    pub on_target_since: f64,
+}
+
+impl BeamState {
+    /// Keeps the time on target while `target` stays the same.
+    pub fn hit(&mut self, target: Option<EntityHolder>, current_time: f64) {
+        if self.target != target {
+            self.on_target_since = current_time;
+        }
+
+        self.target = target;
+    }
+}
+
+pub trait BeamLike {
+    fn draw_beams(&self, rigid_body_set: &RigidBodySet) -> Option<()>;
+}
+
+impl BeamLike for Entity {
+    fn draw_beams(&self, rigid_body_set: &RigidBodySet) -> Option<()> {
+        let transform = self.transform(rigid_body_set)?;
+        let fire_points = self.resource.info.fire_points()?.get_point2s();
+
+        self.weapons
+            .iter()
+            .filter(|v| v.beam.is_some() && v.beam_state.firing)
+            .for_each(|weapon| {
+                if let Some(fire_point) = fire_points.get(weapon.fire_point) {
+                    let origin = transform.transform_point(fire_point);
+                    let beam_transform = Isometry::new(origin.coords, transform.rotation.angle());
+
+                    draw::draw_stretched(
+                        weapon.projectile,
+                        beam_transform,
+                        weapon.beam_state.length,
+                        &Modulation::default(),
+                    );
+                }
+            });
+
+        Some(())
+    }
+}
@@ -0,0 +1,69 @@
+use macroquad::prelude::KeyCode;
+
+use crate::info::settings::pressed;
+
+use super::entity::{Entity, EntityHolder};
+
+/// Makes a ship worth boarding once it's crippled.
+#[derive(Debug, Clone, Copy)]
+pub struct Boardable {
+    /// Hull the player patches up from what's found in the hold.
+    pub salvage: f32,
+}
+
+/// A captured ship flying with the fleet of `leader`.
+#[derive(Debug, Clone, Copy)]
+pub struct Escort {
+    pub leader: EntityHolder,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardingChoice {
+    Loot,
+    Scuttle,
+    Prize,
+}
+
+impl BoardingChoice {
+    pub const ALL: [BoardingChoice; 3] = [
+        BoardingChoice::Loot,
+        BoardingChoice::Scuttle,
+        BoardingChoice::Prize,
+    ];
+
+    pub fn key(self) -> KeyCode {
+        match self {
+            BoardingChoice::Loot => KeyCode::Key1,
+            BoardingChoice::Scuttle => KeyCode::Key2,
+            BoardingChoice::Prize => KeyCode::Key3,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BoardingChoice::Loot => "[1] Loot the cargo",
+            BoardingChoice::Scuttle => "[2] Scuttle her",
+            BoardingChoice::Prize => "[3] Take her as a prize",
+        }
+    }
+}
+
+pub trait BoardingLike {
+    /// The choice pressed this frame, once a ship is boarded.
+    fn boarding_choice(&self) -> Option<BoardingChoice>;
+
+    /// The player and the ships of their fleet.
+    fn is_friendly(&self) -> bool;
+}
+
+impl BoardingLike for Entity {
+    fn boarding_choice(&self) -> Option<BoardingChoice> {
+        self.player?.boarded?;
+
+        pressed(&BoardingChoice::ALL.map(|v| (v.key(), v)))
+    }
+
+    fn is_friendly(&self) -> bool {
+        self.player.is_some() || self.escort.is_some()
+    }
+}
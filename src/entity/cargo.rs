@@ -0,0 +1,295 @@
+use std::f32::consts::PI;
+
+use macroquad::prelude::{get_time, rand};
+use nalgebra::UnitComplex;
+use rapier2d::{
+    math::{Point, Real, Vector},
+    prelude::{MassProperties, RigidBodySet},
+};
+
+use crate::{economy::commodity::CommodityId, world::world_mutator::WorldMutator, ECONOMY};
+
+use super::{
+    entity::Entity,
+    physics::PhysicsLike,
+    pickup::{Pickup, PickupKind},
+};
+
+/// Most units a single jettisoned crate holds.
+const CRATE_SIZE: u32 = 5;
+/// How far behind the ship's center crates are let go, in ship lengths.
+const JETTISON_DISTANCE: Real = 0.8;
+/// Speed crates are pushed away from the ship with.
+const JETTISON_SPEED: Real = 60.0;
+/// Jettisoned crates stay out of reach of the magnet this long.
+const JETTISON_DELAY: f64 = 3.0;
+/// Of the cargo of a destroyed ship, what survives as crates.
+const SPILL_SHARE: f32 = 0.5;
+const SPILL_SPEED: (Real, Real) = (20.0, 90.0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stack {
+    pub commodity: CommodityId,
+    pub amount: u32,
+}
+
+impl Stack {
+    pub fn volume(&self) -> f32 {
+        ECONOMY.commodities[self.commodity].volume * self.amount as f32
+    }
+
+    pub fn mass(&self) -> f32 {
+        ECONOMY.commodities[self.commodity].mass * self.amount as f32
+    }
+}
+
+/// Commodities a ship carries, as long as they fit its volume.
+#[derive(Debug, Clone, Default)]
+pub struct CargoHold {
+    /// Provided by cargo bays.
+    pub capacity: f32,
+    pub stacks: Vec<Stack>,
+}
+
+impl CargoHold {
+    pub fn volume(&self) -> f32 {
+        self.stacks.iter().map(|v| v.volume()).sum()
+    }
+
+    pub fn mass(&self) -> f32 {
+        self.stacks.iter().map(|v| v.mass()).sum()
+    }
+
+    /// Stores `stack` if it fits, merging it with what's already there.
+    pub fn add(&mut self, stack: Stack) -> bool {
+        if self.volume() + stack.volume() > self.capacity {
+            return false;
+        }
+
+        match self
+            .stacks
+            .iter_mut()
+            .find(|v| v.commodity == stack.commodity)
+        {
+            Some(v) => v.amount += stack.amount,
+            None => self.stacks.push(stack),
+        }
+
+        true
+    }
+
+    /// Moves over as much of `other` as fits, the rest stays behind.
+    pub fn take_all(&mut self, other: &mut CargoHold) -> Vec<Stack> {
+        let mut taken = vec![];
+
+        for stack in other.stacks.iter_mut() {
+            let unit = ECONOMY.commodities[stack.commodity].volume;
+            let room = self.capacity - self.volume();
+            let fits = match unit {
+                v if v > 0.0 => ((room / v).max(0.0) as u32).min(stack.amount),
+                _ => stack.amount,
+            };
+            let moved = Stack {
+                amount: fits,
+                ..*stack
+            };
+
+            if fits > 0 && self.add(moved) {
+                stack.amount -= fits;
+                taken.push(moved);
+            }
+        }
+
+        other.stacks.retain(|v| v.amount > 0);
+
+        taken
+    }
+
+    /// Takes up to `amount` of `commodity`, returns what it got.
+    pub fn remove(&mut self, commodity: CommodityId, amount: u32) -> Option<Stack> {
+        let stack = self.stacks.iter_mut().find(|v| v.commodity == commodity)?;
+        let taken = stack.amount.min(amount);
+        stack.amount -= taken;
+
+        self.stacks.retain(|v| v.amount > 0);
+
+        (taken > 0).then_some(Stack {
+            commodity,
+            amount: taken,
+        })
+    }
+}
+
+pub trait CargoLike {
+    /// Sets the mass of the rigid body to the hull's own plus the cargo's.
+    fn update_cargo_mass(&self, rigid_body_set: &mut RigidBodySet) -> Option<()>;
+
+    /// Lets go of a crate of whatever was loaded last, behind the ship.
+    fn jettison(&mut self, rigid_body_set: &mut RigidBodySet) -> Option<WorldMutator>;
+
+    /// Crates with a share of the cargo, scattered from where the ship was
+    /// destroyed. The rest is lost with it.
+    fn spill_cargo(&self, rigid_body_set: &RigidBodySet) -> Option<Vec<WorldMutator>>;
+
+    /// Crates with all of the cargo, scattered around the ship.
+    fn dump_cargo(&self, rigid_body_set: &RigidBodySet) -> Option<Vec<WorldMutator>>;
+}
+
+impl CargoLike for Entity {
+    fn update_cargo_mass(&self, rigid_body_set: &mut RigidBodySet) -> Option<()> {
+        let base = self.physics?.base_mass;
+        let cargo_mass = self.cargo.as_ref().map_or(0.0, |v| v.mass());
+
+        // spread like the hull, so turning gets as sluggish as accelerating
+        let base_inertia = match base.inv_principal_inertia_sqrt {
+            v if v > 0.0 => 1.0 / (v * v),
+            _ => 0.0,
+        };
+        let cargo = MassProperties::new(
+            Point::origin(),
+            cargo_mass,
+            base_inertia * cargo_mass * base.inv_mass,
+        );
+
+        self.get_rigid_body_mut(rigid_body_set)?
+            .set_mass_properties(base + cargo, true);
+
+        Some(())
+    }
+
+    fn jettison(&mut self, rigid_body_set: &mut RigidBodySet) -> Option<WorldMutator> {
+        let cargo = self.cargo.as_mut()?;
+        let commodity = cargo.stacks.last()?.commodity;
+        let stack = cargo.remove(commodity, CRATE_SIZE)?;
+
+        self.update_cargo_mass(rigid_body_set);
+
+        let rigid_body = self.get_rigid_body(rigid_body_set)?;
+        let backward: Vector<Real> = rigid_body.rotation() * -Vector::x();
+        let length = self.resource.width.max(self.resource.height);
+
+        let position = rigid_body.translation() + backward * length * JETTISON_DISTANCE;
+        let linvel = rigid_body.linvel() + backward * JETTISON_SPEED;
+
+        let pickup = Pickup {
+            collectable_after: get_time() + JETTISON_DELAY,
+            ..Pickup::new(PickupKind::Cargo(stack.commodity), stack.amount)
+        };
+
+        pickup.spawn(position, linvel)
+    }
+
+    fn spill_cargo(&self, rigid_body_set: &RigidBodySet) -> Option<Vec<WorldMutator>> {
+        scatter_cargo(self, SPILL_SHARE, rigid_body_set)
+    }
+
+    fn dump_cargo(&self, rigid_body_set: &RigidBodySet) -> Option<Vec<WorldMutator>> {
+        scatter_cargo(self, 1.0, rigid_body_set)
+    }
+}
+
+/// Crates with `share` of the cargo of `entity`, flying off in every direction.
+fn scatter_cargo(
+    entity: &Entity,
+    share: f32,
+    rigid_body_set: &RigidBodySet,
+) -> Option<Vec<WorldMutator>> {
+    let rigid_body = entity.get_rigid_body(rigid_body_set)?;
+    let position = *rigid_body.translation();
+    let linvel = *rigid_body.linvel();
+
+    let mutators = entity
+        .cargo
+        .as_ref()?
+        .stacks
+        .iter()
+        .flat_map(|stack| {
+            let mut left = (stack.amount as f32 * share).round() as u32;
+
+            std::iter::from_fn(move || {
+                let amount = left.min(CRATE_SIZE);
+                left -= amount;
+
+                (amount > 0).then_some((stack.commodity, amount))
+            })
+        })
+        .filter_map(|(commodity, amount)| {
+            let direction = UnitComplex::new(rand::gen_range(-PI, PI));
+            let scatter: Vector<Real> =
+                direction * Vector::x() * rand::gen_range(SPILL_SPEED.0, SPILL_SPEED.1);
+
+            Pickup::new(PickupKind::Cargo(commodity), amount).spawn(position, linvel + scatter)
+        })
+        .collect();
+
+    Some(mutators)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commodity(id: &str) -> CommodityId {
+        ECONOMY.commodities.iter().position(|v| v.id == id).unwrap()
+    }
+
+    fn stack(id: &str, amount: u32) -> Stack {
+        Stack {
+            commodity: commodity(id),
+            amount,
+        }
+    }
+
+    fn hold(capacity: f32) -> CargoHold {
+        CargoHold {
+            capacity,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn add_merges_stacks_of_a_commodity() {
+        let mut cargo = hold(10.0);
+
+        assert!(cargo.add(stack("ore", 3)));
+        assert!(cargo.add(stack("ore", 2)));
+
+        assert_eq!(cargo.stacks, vec![stack("ore", 5)]);
+        assert_eq!(cargo.volume(), stack("ore", 5).volume());
+        assert_eq!(cargo.mass(), stack("ore", 5).mass());
+    }
+
+    #[test]
+    fn add_refuses_what_does_not_fit() {
+        let volume = stack("machinery", 1).volume();
+        let mut cargo = hold(volume * 3.0);
+
+        assert!(cargo.add(stack("machinery", 3)));
+        assert!(!cargo.add(stack("machinery", 1)));
+        assert_eq!(cargo.stacks, vec![stack("machinery", 3)]);
+    }
+
+    #[test]
+    fn remove_takes_up_to_what_is_there() {
+        let mut cargo = hold(10.0);
+        cargo.add(stack("ore", 4));
+        cargo.add(stack("food", 2));
+
+        assert_eq!(cargo.remove(commodity("ore"), 3), Some(stack("ore", 3)));
+        assert_eq!(cargo.remove(commodity("ore"), 3), Some(stack("ore", 1)));
+        assert_eq!(cargo.remove(commodity("ore"), 1), None);
+        assert_eq!(cargo.stacks, vec![stack("food", 2)]);
+    }
+
+    #[test]
+    fn take_all_leaves_what_does_not_fit() {
+        let volume = stack("ore", 1).volume();
+        let mut cargo = hold(volume * 5.0);
+        let mut other = hold(100.0);
+        other.add(stack("ore", 8));
+
+        assert_eq!(cargo.take_all(&mut other), vec![stack("ore", 5)]);
+        assert_eq!(cargo.stacks, vec![stack("ore", 5)]);
+        assert_eq!(other.stacks, vec![stack("ore", 3)]);
+    }
+}
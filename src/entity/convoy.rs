@@ -0,0 +1,19 @@
+use super::entity::EntityHolder;
+
+/// A merchant ship hauling its cargo from one station to another, see
+/// `World::update_convoys`.
+#[derive(Debug, Clone, Copy)]
+pub struct Merchant {
+    pub destination: EntityHolder,
+    /// Set once the player fires on the convoy, its guards fight back then.
+    pub attacked: bool,
+}
+
+/// Security flying with a merchant.
+#[derive(Debug, Clone, Copy)]
+pub struct Guard {
+    /// `None` once the merchant is gone.
+    pub leader: Option<EntityHolder>,
+    /// Station it flies back to without a merchant, and leaves at.
+    pub home: EntityHolder,
+}
@@ -0,0 +1,140 @@
+use macroquad::prelude::{get_time, rand};
+use nalgebra::vector;
+use rapier2d::{
+    math::{Isometry, Real, Vector},
+    prelude::{Collider, ColliderBuilder, RigidBodySet, SharedShape},
+};
+
+use crate::{
+    render::layer::Layer,
+    util::{collision::Collision, fragment::Fragment},
+    world::world_mutator::WorldMutator,
+};
+
+use super::{
+    drawable::Drawable,
+    entity::{Entity, EntityBuilder},
+    physics::PhysicsLike,
+};
+
+const LIFETIME: f64 = 4.0;
+/// Fraction of the lifetime over which debris fades out.
+const FADE_OUT: f64 = 0.5;
+/// Speed pieces get pushed away from the center of the ship with.
+const BURST_SPEED: (Real, Real) = (60.0, 220.0);
+const SPIN: Real = 3.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Debris {
+    /// Index into the fragments of the resource.
+    pub fragment: usize,
+    pub spawned: f64,
+    pub lifetime: f64,
+}
+
+impl Debris {
+    pub fn fragment(&self, entity: &Entity) -> Option<&'static Fragment> {
+        entity.resource.fragments.get(self.fragment)
+    }
+}
+
+pub trait DebrisLike {
+    /// One piece of debris per fragment of the resource, flying apart from
+    /// where the entity was.
+    fn break_apart(&self, rigid_body_set: &RigidBodySet) -> Option<Vec<WorldMutator>>;
+
+    /// The collider of the fragment this piece of debris carries.
+    fn debris_collider(&self) -> Option<Collider>;
+
+    fn update_debris(&mut self, current_time: f64) -> Option<WorldMutator>;
+}
+
+impl DebrisLike for Entity {
+    fn break_apart(&self, rigid_body_set: &RigidBodySet) -> Option<Vec<WorldMutator>> {
+        let rigid_body = self.get_rigid_body(rigid_body_set)?;
+        let transform = *rigid_body.position();
+        let linvel = *rigid_body.linvel();
+        let angvel = rigid_body.angvel();
+
+        let drawable = self.drawable.map(|v| Drawable {
+            layer: Layer::Debris,
+            ..v
+        });
+
+        let mutators = self
+            .resource
+            .fragments
+            .iter()
+            .enumerate()
+            .map(|(i, fragment)| {
+                let debris = Debris {
+                    fragment: i,
+                    spawned: get_time(),
+                    lifetime: LIFETIME * rand::gen_range(0.75, 1.25),
+                };
+
+                // inherit the motion of that point of the ship, then burst outward
+                let offset = transform.rotation * fragment.offset;
+                let burst = offset
+                    .try_normalize(Real::EPSILON)
+                    .unwrap_or_else(Vector::x)
+                    * rand::gen_range(BURST_SPEED.0, BURST_SPEED.1);
+                let velocity = linvel + vector!(-offset.y, offset.x) * angvel + burst;
+                let spin = angvel + rand::gen_range(-SPIN, SPIN);
+
+                let position = Isometry::new(
+                    transform.translation.vector + offset,
+                    transform.rotation.angle(),
+                );
+
+                let mut builder = EntityBuilder::new(self.resource);
+                if let Some(drawable) = drawable {
+                    builder.drawable(drawable);
+                }
+
+                builder
+                    .debris(debris)
+                    .build_mutator(Box::new(move |entity, rigid_body_set| {
+                        let rigid_body = entity.get_rigid_body_mut(rigid_body_set)?;
+
+                        rigid_body.set_position(position, false);
+                        rigid_body.set_linvel(velocity, true);
+                        rigid_body.set_angvel(spin, true);
+
+                        None
+                    }))
+            })
+            .collect();
+
+        Some(mutators)
+    }
+
+    fn debris_collider(&self) -> Option<Collider> {
+        let fragment = self.debris?.fragment(self)?;
+        let shape = SharedShape::convex_hull(&fragment.points)?;
+        let groups = *Collision::from_str("debris");
+
+        Some(
+            ColliderBuilder::new(shape)
+                .collision_groups(groups)
+                .solver_groups(groups)
+                .build(),
+        )
+    }
+
+    fn update_debris(&mut self, current_time: f64) -> Option<WorldMutator> {
+        let debris = self.debris?;
+        let elapsed = current_time - debris.spawned;
+
+        if elapsed > debris.lifetime {
+            return Some(WorldMutator::Remove(self.entity_holder?));
+        }
+
+        let remaining = (debris.lifetime - elapsed) / (debris.lifetime * FADE_OUT);
+        if let Some(drawable) = self.drawable.as_mut() {
+            drawable.alpha = remaining.min(1.0) as f32;
+        }
+
+        None
+    }
+}
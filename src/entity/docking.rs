@@ -0,0 +1,75 @@
+use macroquad::prelude::KeyCode;
+
+use crate::{info::settings::pressed, util::station::Service};
+
+use super::entity::{Entity, EntityHolder};
+
+/// Docked at a station, browsing one of its services.
+#[derive(Debug, Clone, Copy)]
+pub struct Docked {
+    pub station: EntityHolder,
+    pub service: Service,
+    /// Row of the service the player has picked.
+    pub selection: usize,
+    /// Shipyard only, index into the modules that fit the selected slot.
+    pub offer: usize,
+}
+
+impl Docked {
+    pub fn new(station: EntityHolder, service: Service) -> Self {
+        Self {
+            station,
+            service,
+            selection: 0,
+            offer: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StationCommand {
+    Open(Service),
+    Up,
+    Down,
+    Previous,
+    Next,
+    /// Buy, install, repair or accept, depending on the service.
+    Buy,
+    Sell,
+    Undock,
+}
+
+impl StationCommand {
+    const KEYS: [(KeyCode, StationCommand); 11] = [
+        (KeyCode::Key1, StationCommand::Open(Service::Market)),
+        (KeyCode::Key2, StationCommand::Open(Service::Shipyard)),
+        (KeyCode::Key3, StationCommand::Open(Service::Repair)),
+        (KeyCode::Key4, StationCommand::Open(Service::Missions)),
+        (KeyCode::Up, StationCommand::Up),
+        (KeyCode::Down, StationCommand::Down),
+        (KeyCode::Left, StationCommand::Previous),
+        (KeyCode::Right, StationCommand::Next),
+        (KeyCode::B, StationCommand::Buy),
+        (KeyCode::N, StationCommand::Sell),
+        (KeyCode::U, StationCommand::Undock),
+    ];
+}
+
+pub trait DockingLike {
+    /// The command pressed this frame, while docked.
+    fn station_command(&self) -> Option<StationCommand>;
+
+    fn is_docked(&self) -> bool;
+}
+
+impl DockingLike for Entity {
+    fn station_command(&self) -> Option<StationCommand> {
+        self.player?.docked?;
+
+        pressed(&StationCommand::KEYS)
+    }
+
+    fn is_docked(&self) -> bool {
+        self.player.is_some_and(|v| v.docked.is_some())
+    }
+}
@@ -1,35 +1,97 @@
-use rapier2d::prelude::{ColliderSet, RigidBodySet};
+use macroquad::prelude::{get_time, Color, WHITE};
+use rapier2d::prelude::RigidBodySet;
 
-use crate::util::{draw, resource::Resource};
+use crate::{
+    render::layer::Layer,
+    util::{
+        draw::{self, Modulation},
+        resource::Resource,
+    },
+};
 
-use super::{entity::Entity, physics::PhysicsLike};
+use super::{animation::AnimationLike, entity::Entity, physics::PhysicsLike};
+
+/// How long a hit flash takes to fade out, in seconds.
+const FLASH_DURATION: f64 = 0.15;
 
 #[derive(Clone, Copy)]
 pub struct Drawable<'a> {
     pub resource: &'a Resource,
+    pub layer: Layer,
+
+    /// Multiplied with the colors of the resource, e.g. for faction colors.
+    pub tint: Color,
+    pub alpha: f32,
+
+    pub flash_color: Color,
+    pub flash_started: f64,
 }
 
 impl Drawable<'_> {
     pub fn from_resource(resource: &Resource) -> Option<Drawable> {
-        Some(Drawable { resource })
+        let layer = resource.info.layer().unwrap_or_default();
+
+        Some(Drawable {
+            resource,
+            layer,
+            tint: WHITE,
+            alpha: 1.0,
+            flash_color: WHITE,
+            flash_started: f64::NEG_INFINITY,
+        })
+    }
+
+    pub fn tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+
+        return self;
+    }
+
+    /// Starts a flash in `color` that fades out over `FLASH_DURATION`.
+    pub fn flash(&mut self, color: Color, current_time: f64) {
+        self.flash_color = color;
+        self.flash_started = current_time;
+    }
+
+    pub fn modulation(&self, current_time: f64) -> Modulation {
+        let elapsed = current_time - self.flash_started;
+        let strength = (1.0 - elapsed / FLASH_DURATION).clamp(0.0, 1.0) as f32;
+
+        let mut flash = self.flash_color;
+        flash.a *= strength;
+
+        Modulation {
+            tint: self.tint,
+            flash,
+            alpha: self.alpha,
+        }
     }
 }
 
 pub trait DrawableLike {
-    fn draw(&self, rigid_body_set: &RigidBodySet, collider_set: &ColliderSet) -> Option<()>;
+    fn draw(&self, rigid_body_set: &RigidBodySet) -> Option<()>;
 }
 
 impl DrawableLike for Entity {
-    fn draw(&self, rigid_body_set: &RigidBodySet, collider_set: &ColliderSet) -> Option<()> {
-        let resource = self.drawable.as_ref()?.resource;
+    fn draw(&self, rigid_body_set: &RigidBodySet) -> Option<()> {
+        let drawable = self.drawable.as_ref()?;
+        let resource = drawable.resource;
         let transform = self.transform(rigid_body_set)?;
 
-        draw::draw(resource, transform);
+        let current_time = get_time();
+        let mut modulation = drawable.modulation(current_time);
+        modulation.tint = self.status_effects.tint(modulation.tint, current_time);
+
+        if let Some(debris) = self.debris {
+            draw::draw_centered(&debris.fragment(self)?.mesh, transform, &modulation);
+
+            return Some(());
+        }
 
-        if cfg!(debug_assertions) {
-            let rigid_body = self.get_rigid_body(rigid_body_set)?;
+        draw::draw(resource, transform, &modulation);
 
-            draw::draw_colliders(rigid_body, collider_set);
+        if let Some(frame) = self.animation_frame(current_time) {
+            draw::draw_mesh(resource, frame, transform, &modulation);
         }
 
         Some(())
@@ -1,7 +1,7 @@
 use generational_arena::Index;
 
 use crate::{
-    util::resource::Resource,
+    util::{faction::Faction, resource::Resource},
     world::{
         world::World,
         world_mutator::{PostInitFn, WorldMutator},
@@ -9,22 +9,60 @@ use crate::{
 };
 
 use super::{
-    drawable::Drawable, physics::Physics, player::Player, projectile::projectile::Projectile,
+    ai::Ai,
+    animation::Animation,
+    boarding::{Boardable, Escort},
+    cargo::CargoHold,
+    convoy::{Guard, Merchant},
+    debris::Debris,
+    drawable::Drawable,
+    health::Health,
+    inventory::Inventory,
+    loadout::{Loadout, LoadoutLike},
+    physics::Physics,
+    pickup::Pickup,
+    player::Player,
+    projectile::projectile::Projectile,
+    ram::RamPlating,
+    reactor::Reactor,
+    station::Station,
+    status::StatusEffects,
+    thrusters::Thrusters,
+    weapon::Weapon,
 };
 
 pub type EntityHolder = Index;
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Entity {
     pub entity_holder: Option<EntityHolder>,
     pub resource: &'static Resource,
     pub physics: Option<Physics>,
     pub drawable: Option<Drawable<'static>>,
+    pub animation: Option<Animation>,
     pub player: Option<Player>,
     pub projectile: Option<Projectile>,
+    pub health: Option<Health>,
+    pub ai: Option<Ai>,
+    pub weapons: Vec<Weapon>,
+    pub debris: Option<Debris>,
+    pub ram_plating: Option<RamPlating>,
+    pub status_effects: StatusEffects,
+    pub boardable: Option<Boardable>,
+    pub escort: Option<Escort>,
+    pub pickup: Option<Pickup>,
+    pub inventory: Option<Inventory>,
+    pub cargo: Option<CargoHold>,
+    pub thrusters: Option<Thrusters>,
+    pub reactor: Option<Reactor>,
+    pub faction: Option<&'static Faction>,
+    pub station: Option<Station>,
+    pub merchant: Option<Merchant>,
+    pub guard: Option<Guard>,
+    pub loadout: Option<Loadout>,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct EntityBuilder {
     entity: Entity,
 }
@@ -37,26 +75,45 @@ impl EntityBuilder {
                 resource,
                 physics: None,
                 drawable: None,
+                animation: None,
                 player: None,
                 projectile: None,
+                health: None,
+                ai: None,
+                weapons: vec![],
+                debris: None,
+                ram_plating: None,
+                status_effects: StatusEffects::default(),
+                boardable: None,
+                escort: None,
+                pickup: None,
+                inventory: None,
+                cargo: None,
+                thrusters: None,
+                reactor: None,
+                faction: None,
+                station: None,
+                merchant: None,
+                guard: None,
+                loadout: None,
             },
         }
     }
 
-    pub fn force_build(self) -> Entity {
-        self.entity
+    pub fn force_build(&self) -> Entity {
+        self.entity.clone()
     }
 
-    pub fn build_mutator(self, post_init: PostInitFn) -> WorldMutator {
-        WorldMutator::Add(self.entity, post_init)
+    pub fn build_mutator(&self, post_init: PostInitFn) -> WorldMutator {
+        WorldMutator::Add(Box::new(self.entity.clone()), post_init)
     }
 
-    pub fn build_no_postinit(self) -> WorldMutator {
-        WorldMutator::Add(self.entity, Box::new(|_, _| None))
+    pub fn build_no_postinit(&self) -> WorldMutator {
+        WorldMutator::Add(Box::new(self.entity.clone()), Box::new(|_, _| None))
     }
 
-    pub fn build<'a>(self, world: &'a mut World, post_init: PostInitFn) -> Option<EntityHolder> {
-        world.add_entity(self.entity, post_init)
+    pub fn build<'a>(&self, world: &'a mut World, post_init: PostInitFn) -> Option<EntityHolder> {
+        world.add_entity(self.entity.clone(), post_init)
     }
 
     pub fn drawable(&mut self, drawable: Drawable<'static>) -> &mut Self {
@@ -65,6 +122,12 @@ impl EntityBuilder {
         return self;
     }
 
+    pub fn animation(&mut self, animation: Animation) -> &mut Self {
+        self.entity.animation = Some(animation);
+
+        return self;
+    }
+
     pub fn player(&mut self, player: Player) -> &mut Self {
         self.entity.player = Some(player);
 
@@ -76,4 +139,97 @@ impl EntityBuilder {
 
         return self;
     }
+
+    pub fn health(&mut self, health: Health) -> &mut Self {
+        self.entity.health = Some(health);
+
+        return self;
+    }
+
+    pub fn ai(&mut self, ai: Ai) -> &mut Self {
+        self.entity.ai = Some(ai);
+
+        return self;
+    }
+
+    pub fn weapon(&mut self, weapon: Weapon) -> &mut Self {
+        self.entity.weapons.push(weapon);
+
+        return self;
+    }
+
+    pub fn debris(&mut self, debris: Debris) -> &mut Self {
+        self.entity.debris = Some(debris);
+
+        return self;
+    }
+
+    pub fn boardable(&mut self, boardable: Boardable) -> &mut Self {
+        self.entity.boardable = Some(boardable);
+
+        return self;
+    }
+
+    pub fn pickup(&mut self, pickup: Pickup) -> &mut Self {
+        self.entity.pickup = Some(pickup);
+
+        return self;
+    }
+
+    pub fn inventory(&mut self, inventory: Inventory) -> &mut Self {
+        self.entity.inventory = Some(inventory);
+
+        return self;
+    }
+
+    /// Also derives the stats of the ship from it, so it goes after
+    /// `health` and `inventory`.
+    pub fn loadout(&mut self, loadout: Loadout) -> &mut Self {
+        self.entity.loadout = Some(loadout);
+        self.entity.apply_loadout();
+
+        return self;
+    }
+
+    pub fn faction(&mut self, faction: &'static Faction) -> &mut Self {
+        self.entity.faction = Some(faction);
+
+        return self;
+    }
+
+    pub fn station(&mut self, station: Station) -> &mut Self {
+        self.entity.station = Some(station);
+
+        return self;
+    }
+
+    pub fn cargo(&mut self, cargo: CargoHold) -> &mut Self {
+        self.entity.cargo = Some(cargo);
+
+        return self;
+    }
+
+    pub fn merchant(&mut self, merchant: Merchant) -> &mut Self {
+        self.entity.merchant = Some(merchant);
+
+        return self;
+    }
+
+    pub fn guard(&mut self, guard: Guard) -> &mut Self {
+        self.entity.guard = Some(guard);
+
+        return self;
+    }
+
+    pub fn thrusters(&mut self, thrusters: Thrusters) -> &mut Self {
+        self.entity.thrusters = Some(thrusters);
+
+        return self;
+    }
+
+    pub fn ram_plating(&mut self, ram_plating: RamPlating) -> &mut Self {
+        self.entity.ram_plating = Some(ram_plating);
+
+        return self;
+    }
 }
@@ -0,0 +1,211 @@
+use macroquad::prelude::{get_time, WHITE};
+use rapier2d::{
+    math::{Point, Real},
+    prelude::RigidBodySet,
+};
+
+use super::{
+    entity::{Entity, EntityHolder},
+    physics::PhysicsLike,
+    reactor::System,
+    shield::{Facing, Shields},
+};
+
+/// Below this fraction of its hull a ship counts as damaged.
+const DAMAGED_FRACTION: f32 = 0.5;
+/// Armor never stops more than this fraction of a hit.
+const MAX_ARMOR_FRACTION: f32 = 0.8;
+/// Reactor energy per point of shield strength regenerated, on any facing.
+const SHIELD_ENERGY: f32 = 0.25;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Health {
+    pub hull: f32,
+    pub max_hull: f32,
+    /// Flat reduction of every hit that gets through the shields.
+    pub armor: f32,
+    pub shields: Option<Shields>,
+    /// Below this fraction of its hull the ship goes dead in the water and can
+    /// be boarded.
+    pub crippled_fraction: Option<f32>,
+}
+
+impl Health {
+    pub fn new(max_hull: f32) -> Self {
+        Self {
+            hull: max_hull,
+            max_hull,
+            armor: 0.0,
+            shields: None,
+            crippled_fraction: None,
+        }
+    }
+
+    pub fn armor(mut self, armor: f32) -> Self {
+        self.armor = armor;
+
+        return self;
+    }
+
+    pub fn shields(mut self, shields: Shields) -> Self {
+        self.shields = Some(shields);
+
+        return self;
+    }
+
+    pub fn crippled_below(mut self, fraction: f32) -> Self {
+        self.crippled_fraction = Some(fraction);
+
+        return self;
+    }
+
+    /// Back to full hull and shields.
+    pub fn restore(&mut self) {
+        self.hull = self.max_hull;
+
+        if let Some(shields) = self.shields.as_mut() {
+            shields.strength = [shields.max_strength; 4];
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Hit {
+    pub damage: f32,
+    /// Where it hit in world space, decides which shield facing takes it.
+    pub point: Option<Point<Real>>,
+    /// Set for continuous damage like beams, which arrives in slices of `dt`.
+    /// Armor then applies per second instead of per slice.
+    pub dt: Option<f32>,
+    /// Goes straight to the armor, e.g. burning hulls.
+    pub ignores_shields: bool,
+    /// Who dealt it, if anyone.
+    pub source: Option<EntityHolder>,
+}
+
+impl Hit {
+    pub fn new(damage: f32, point: Option<Point<Real>>) -> Self {
+        Self {
+            damage,
+            point,
+            dt: None,
+            ignores_shields: false,
+            source: None,
+        }
+    }
+
+    pub fn continuous(damage_per_second: f32, dt: f32, point: Option<Point<Real>>) -> Self {
+        Self {
+            damage: damage_per_second * dt,
+            point,
+            dt: Some(dt),
+            ignores_shields: false,
+            source: None,
+        }
+    }
+
+    pub fn ignoring_shields(mut self) -> Self {
+        self.ignores_shields = true;
+
+        return self;
+    }
+
+    pub fn by(mut self, source: EntityHolder) -> Self {
+        self.source = Some(source);
+
+        return self;
+    }
+
+    fn after_armor(&self, damage: f32, armor: f32) -> f32 {
+        let reduction = armor * self.dt.unwrap_or(1.0);
+
+        (damage - reduction).max(damage * (1.0 - MAX_ARMOR_FRACTION))
+    }
+}
+
+pub trait HealthLike {
+    /// Runs the hit through shields and armor. Returns whether the entity got
+    /// destroyed by it.
+    fn damage(&mut self, hit: Hit, rigid_body_set: &RigidBodySet) -> Option<bool>;
+
+    fn update_health(&mut self, current_time: f64, dt: f32) -> Option<()>;
+
+    fn is_damaged(&self) -> bool;
+
+    /// Thrust and weapons are offline, but it's still in one piece.
+    fn is_crippled(&self) -> bool;
+
+    fn is_destroyed(&self) -> bool;
+}
+
+impl HealthLike for Entity {
+    fn damage(&mut self, hit: Hit, rigid_body_set: &RigidBodySet) -> Option<bool> {
+        let current_time = get_time();
+        let transform = self.transform(rigid_body_set);
+        let health = self.health.as_mut()?;
+
+        let mut damage = hit.damage;
+
+        if let Some(shields) = health.shields.as_mut().filter(|_| !hit.ignores_shields) {
+            // without an impact point, fall back to the front
+            let facing = transform
+                .zip(hit.point)
+                .map_or(Facing::Front, |(transform, point)| {
+                    Facing::from_impact(&transform, point)
+                });
+
+            damage = shields.absorb(facing, damage, current_time);
+        }
+
+        if damage <= 0.0 {
+            return Some(false);
+        }
+
+        health.hull = (health.hull - hit.after_armor(damage, health.armor)).max(0.0);
+        let destroyed = health.hull <= 0.0;
+
+        if let Some(drawable) = self.drawable.as_mut() {
+            drawable.flash(WHITE, current_time);
+        }
+
+        Some(destroyed)
+    }
+
+    /// Shields regenerate off the reactor, as far as its energy goes, and not
+    /// at all while it's dry.
+    fn update_health(&mut self, current_time: f64, dt: f32) -> Option<()> {
+        let shields = self.health.as_mut()?.shields.as_mut()?;
+
+        match self.reactor.as_mut() {
+            Some(reactor) if reactor.is_dry() => {}
+            Some(reactor) => {
+                let rate = reactor.factor(System::Shields);
+                // what a full regeneration would take, on a copy
+                let wanted = { *shields }.regenerate(current_time, dt, rate);
+                let supplied = reactor.drain(wanted * SHIELD_ENERGY);
+                shields.regenerate(current_time, dt, rate * supplied);
+            }
+            None => {
+                shields.regenerate(current_time, dt, 1.0);
+            }
+        }
+
+        Some(())
+    }
+
+    fn is_damaged(&self) -> bool {
+        self.health
+            .is_some_and(|v| v.hull < v.max_hull * DAMAGED_FRACTION)
+    }
+
+    fn is_crippled(&self) -> bool {
+        self.health.is_some_and(|v| {
+            v.crippled_fraction
+                .is_some_and(|f| v.hull > 0.0 && v.hull <= v.max_hull * f)
+        })
+    }
+
+    fn is_destroyed(&self) -> bool {
+        self.health.is_some_and(|v| v.hull <= 0.0)
+    }
+}
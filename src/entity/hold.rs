@@ -0,0 +1,37 @@
+use super::entity::EntityHolder;
+
+/// The player holding position at `target` for a while, to board or dock
+/// with it.
+#[derive(Debug, Clone, Copy)]
+pub struct Hold {
+    pub target: EntityHolder,
+    pub started: f64,
+}
+
+impl Hold {
+    /// Keeps holding at the same `target`, starts over at a new one and lets
+    /// go without one.
+    pub fn follow(
+        hold: Option<Hold>,
+        target: Option<EntityHolder>,
+        current_time: f64,
+    ) -> Option<Hold> {
+        match (hold, target) {
+            (Some(hold), Some(target)) if hold.target == target => Some(hold),
+            (_, Some(target)) => Some(Hold {
+                target,
+                started: current_time,
+            }),
+            _ => None,
+        }
+    }
+
+    /// From 0 to 1, over `duration`.
+    pub fn progress(&self, current_time: f64, duration: f64) -> f32 {
+        ((current_time - self.started) / duration).clamp(0.0, 1.0) as f32
+    }
+
+    pub fn is_done(&self, current_time: f64, duration: f64) -> bool {
+        current_time - self.started >= duration
+    }
+}
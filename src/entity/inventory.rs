@@ -0,0 +1,69 @@
+use super::{cargo::Stack, entity::Entity, pickup::PickupKind};
+
+/// Hull a repair kit patches up.
+const REPAIR_KIT_HULL: f32 = 50.0;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Inventory {
+    pub credits: u32,
+    /// Shared by every weapon that needs it, e.g. missiles.
+    pub ammo: u32,
+    pub repair_kits: u32,
+}
+
+impl Inventory {
+    pub fn ammo(mut self, ammo: u32) -> Self {
+        self.ammo = ammo;
+
+        return self;
+    }
+
+    /// Cargo doesn't go in here but into the hold, see `InventoryLike::take`.
+    pub fn add(&mut self, kind: PickupKind, amount: u32) -> bool {
+        let slot = match kind {
+            PickupKind::Credits => &mut self.credits,
+            PickupKind::Cargo(_) => return false,
+            PickupKind::Ammo => &mut self.ammo,
+            PickupKind::Repair => &mut self.repair_kits,
+        };
+
+        *slot += amount;
+
+        true
+    }
+}
+
+pub trait InventoryLike {
+    /// Uses up a repair kit if the hull needs one.
+    fn use_repair_kit(&mut self) -> Option<()>;
+
+    /// Stows `amount` of `kind`, cargo in the hold if there's room for it.
+    fn take(&mut self, kind: PickupKind, amount: u32) -> bool;
+}
+
+impl InventoryLike for Entity {
+    fn use_repair_kit(&mut self) -> Option<()> {
+        let inventory = self.inventory.as_mut()?;
+        let health = self.health.as_mut()?;
+
+        if inventory.repair_kits == 0 || health.hull >= health.max_hull {
+            return None;
+        }
+
+        inventory.repair_kits -= 1;
+        health.hull = (health.hull + REPAIR_KIT_HULL).min(health.max_hull);
+
+        Some(())
+    }
+
+    fn take(&mut self, kind: PickupKind, amount: u32) -> bool {
+        if let PickupKind::Cargo(commodity) = kind {
+            return self
+                .cargo
+                .as_mut()
+                .is_some_and(|v| v.add(Stack { commodity, amount }));
+        }
+
+        self.inventory.as_mut().is_some_and(|v| v.add(kind, amount))
+    }
+}
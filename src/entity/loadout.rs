@@ -0,0 +1,156 @@
+use crate::util::{
+    module::{Catalogue, Module, ModuleStats},
+    resource::Resource,
+};
+
+use super::{
+    cargo::CargoHold, entity::Entity, health::Health, ram::RamPlating, reactor::Reactor,
+    shield::Shields, thrusters::Thrusters, weapon::Weapon,
+};
+
+/// The modules installed into the slots of a ship. Its stats are derived from
+/// them, see `LoadoutLike::apply_loadout`.
+#[derive(Clone)]
+pub struct Loadout {
+    /// Hull of the bare frame, before any reinforcements.
+    pub base_hull: f32,
+    /// One per slot of the resource, in the same order.
+    pub modules: Vec<Option<&'static Module>>,
+}
+
+impl Loadout {
+    pub fn new(resource: &Resource, base_hull: f32) -> Self {
+        Self {
+            base_hull,
+            modules: vec![None; resource.info.slots().len()],
+        }
+    }
+
+    /// Installs the modules with these ids into the slots in order, an empty
+    /// id leaves a slot empty.
+    pub fn with_modules(
+        mut self,
+        resource: &Resource,
+        catalogue: &'static Catalogue,
+        ids: &[&str],
+    ) -> Self {
+        ids.iter()
+            .enumerate()
+            .filter(|(_, id)| !id.is_empty())
+            .for_each(|(slot, id)| match catalogue.get(id) {
+                Some(module) => {
+                    if self.install(resource, slot, module).is_none() {
+                        log::warn!("{} doesn't fit into slot {}", id, slot);
+                    }
+                }
+                None => log::warn!("unknown module {}", id),
+            });
+
+        return self;
+    }
+
+    /// Puts `module` into `slot` if it fits there, returns what was in it.
+    pub fn install(
+        &mut self,
+        resource: &Resource,
+        slot: usize,
+        module: &'static Module,
+    ) -> Option<Option<&'static Module>> {
+        let kind = resource.info.slots().get(slot)?.kind;
+
+        if kind != module.kind {
+            return None;
+        }
+
+        Some(self.modules.get_mut(slot)?.replace(module))
+    }
+
+    fn installed(&self) -> impl Iterator<Item = &'static Module> + '_ {
+        self.modules.iter().flatten().copied()
+    }
+}
+
+pub trait LoadoutLike {
+    /// Derives weapons, thrusters, reactor, health, shields and cargo space
+    /// from the installed modules. Damage the ship has taken carries over.
+    fn apply_loadout(&mut self) -> Option<()>;
+}
+
+impl LoadoutLike for Entity {
+    fn apply_loadout(&mut self) -> Option<()> {
+        let loadout = self.loadout.clone()?;
+        let slots = self.resource.info.slots();
+
+        self.weapons = slots
+            .iter()
+            .zip(loadout.modules.iter())
+            .filter_map(|(slot, module)| match &(*module)?.stats {
+                ModuleStats::Weapon(preset) => Weapon::preset(preset.as_str(), slot.fire_point?),
+                _ => None,
+            })
+            .collect();
+
+        let mut thrusters = None;
+        let mut reactor = None;
+        let mut shields = None;
+        let mut cargo_capacity = 0;
+        let mut armor = 0.0;
+        let mut hull = loadout.base_hull;
+        let mut ram_plating = false;
+
+        for module in loadout.installed() {
+            match module.stats {
+                ModuleStats::Weapon(_) => {}
+                ModuleStats::Engine { .. } => thrusters = Thrusters::from_module(module),
+                ModuleStats::Reactor { energy, recharge } => {
+                    reactor = Some(Reactor::new(energy, recharge))
+                }
+                ModuleStats::Shield {
+                    strength,
+                    regen,
+                    regen_delay,
+                } => shields = Some(Shields::new(strength, regen, regen_delay)),
+                ModuleStats::Cargo { capacity } => cargo_capacity += capacity,
+                ModuleStats::Utility {
+                    armor: module_armor,
+                    hull: module_hull,
+                    ram_plating: module_ram_plating,
+                } => {
+                    armor += module_armor;
+                    hull += module_hull;
+                    ram_plating |= module_ram_plating;
+                }
+            }
+        }
+
+        self.thrusters = thrusters.map(|v| Thrusters {
+            flight_assist: self.thrusters.map_or(v.flight_assist, |v| v.flight_assist),
+            ..v
+        });
+
+        // without a reactor module the ship runs dry, the pips stay where
+        // the pilot put them
+        let mut reactor = reactor.unwrap_or(Reactor::new(0.0, 0.0));
+        if let Some(previous) = self.reactor {
+            reactor.energy = previous.energy.min(reactor.capacity);
+            reactor.pips = previous.pips;
+        }
+        self.reactor = Some(reactor);
+
+        let damage = self.health.map_or(0.0, |v| v.max_hull - v.hull);
+        let mut health = Health::new(hull).armor(armor);
+        health.hull = (hull - damage).max(1.0);
+        health.shields = shields;
+        health.crippled_fraction = self.health.and_then(|v| v.crippled_fraction);
+        self.health = Some(health);
+
+        self.ram_plating = ram_plating.then(RamPlating::default);
+
+        // whatever no longer fits stays aboard, it just can't take any more
+        if cargo_capacity > 0 || self.cargo.is_some() {
+            self.cargo.get_or_insert_with(CargoHold::default).capacity = cargo_capacity as f32;
+        }
+
+        Some(())
+    }
+}
@@ -1,5 +1,25 @@
+pub mod ai;
+pub mod animation;
+pub mod beam;
+pub mod boarding;
+pub mod cargo;
+pub mod convoy;
+pub mod debris;
+pub mod docking;
 pub mod drawable;
 pub mod entity;
+pub mod health;
+pub mod hold;
+pub mod inventory;
+pub mod loadout;
 pub mod physics;
+pub mod pickup;
 pub mod player;
 pub mod projectile;
+pub mod ram;
+pub mod reactor;
+pub mod shield;
+pub mod station;
+pub mod status;
+pub mod thrusters;
+pub mod weapon;
@@ -2,17 +2,40 @@ use nalgebra::{vector, Complex, Unit};
 use rapier2d::{
     math::{Isometry, Point, Real, Rotation, Vector},
     parry::utils::IsometryOpt,
-    prelude::{Collider, ColliderHandle, ColliderSet, RigidBody, RigidBodyHandle, RigidBodySet},
+    prelude::{
+        Collider, ColliderHandle, ColliderSet, MassProperties, RigidBody, RigidBodyHandle,
+        RigidBodySet,
+    },
 };
 
 use crate::util::screen::crop_to_world;
 
-use super::entity::Entity;
+use super::entity::{Entity, EntityHolder};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Physics {
     pub rigid_body_handle: RigidBodyHandle,
     pub collider_handle: ColliderHandle,
+    /// Docking sensor of stations.
+    pub dock_handle: Option<ColliderHandle>,
+    /// Velocity at the end of the last update, i.e. going into the physics
+    /// step that produced the current contacts, before it resolved them.
+    pub previous_velocity: Vector<Real>,
+    /// Of the hull alone, as computed from its colliders. Cargo is added on
+    /// top, see `CargoLike::update_cargo_mass`.
+    pub base_mass: MassProperties,
+}
+
+/// Packs an entity holder into collider user data, so that physics events can be
+/// traced back to the entity that owns the collider.
+pub fn holder_to_user_data(holder: EntityHolder) -> u128 {
+    let (index, generation) = holder.into_raw_parts();
+
+    ((generation as u128) << 64) | index as u128
+}
+
+pub fn user_data_to_holder(user_data: u128) -> EntityHolder {
+    EntityHolder::from_raw_parts(user_data as u64 as usize, (user_data >> 64) as u64)
 }
 
 pub trait PhysicsLike {
@@ -31,6 +54,7 @@ pub trait PhysicsLike {
     fn rotation(&self, rigid_body_set: &RigidBodySet) -> Option<Rotation<Real>>;
 
     fn update_entity_position(&self, rigid_body_set: &mut RigidBodySet) -> Option<()>;
+    fn remember_velocity(&mut self, rigid_body_set: &RigidBodySet) -> Option<()>;
 }
 
 impl PhysicsLike for Entity {
@@ -86,4 +110,11 @@ impl PhysicsLike for Entity {
 
         Some(())
     }
+
+    fn remember_velocity(&mut self, rigid_body_set: &RigidBodySet) -> Option<()> {
+        let velocity = self.velocity(rigid_body_set)?;
+        self.physics.as_mut()?.previous_velocity = velocity;
+
+        Some(())
+    }
 }
@@ -0,0 +1,179 @@
+use std::f32::consts::PI;
+
+use macroquad::prelude::{get_time, rand, Color};
+use nalgebra::UnitComplex;
+use rapier2d::{
+    math::{Isometry, Real, Vector},
+    prelude::RigidBodySet,
+};
+
+use crate::{
+    economy::commodity::CommodityId, world::world_mutator::WorldMutator, CRATE, ECONOMY, PICKUP,
+};
+
+use super::{
+    drawable::Drawable,
+    entity::{Entity, EntityBuilder},
+    physics::PhysicsLike,
+};
+
+const LIFETIME: f64 = 20.0;
+/// Crates hang around longer, they're worth coming back for.
+const CRATE_LIFETIME: f64 = 60.0;
+/// Fraction of the lifetime over which pickups fade out.
+const FADE_OUT: f64 = 0.2;
+/// How many pickups a destroyed ship drops, inclusive.
+const DROPS: (u32, u32) = (1, 3);
+/// Speed the random impulse gives a pickup on top of the ship's velocity.
+const SCATTER_SPEED: (Real, Real) = (20.0, 80.0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickupKind {
+    Credits,
+    /// A crate of a commodity, goes into the cargo hold.
+    Cargo(CommodityId),
+    Ammo,
+    Repair,
+}
+
+impl PickupKind {
+    /// Weighted toward credits, repair kits are rare. Without any
+    /// commodities, cargo turns into credits.
+    fn roll() -> Self {
+        let commodities = ECONOMY.commodities.len();
+
+        match rand::gen_range(0, 10) {
+            0..=4 => PickupKind::Credits,
+            5 | 6 if commodities == 0 => PickupKind::Credits,
+            5 | 6 => PickupKind::Cargo(rand::gen_range(0, commodities)),
+            7 | 8 => PickupKind::Ammo,
+            _ => PickupKind::Repair,
+        }
+    }
+
+    fn roll_amount(self) -> u32 {
+        match self {
+            PickupKind::Credits => rand::gen_range(10, 50),
+            PickupKind::Cargo(_) => rand::gen_range(1, 4),
+            PickupKind::Ammo => rand::gen_range(2, 5),
+            PickupKind::Repair => 1,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PickupKind::Credits => "credits",
+            PickupKind::Cargo(commodity) => &ECONOMY.commodities[commodity].name,
+            PickupKind::Ammo => "ammo",
+            PickupKind::Repair => "repair kit",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            PickupKind::Credits => Color::new(1.0, 0.85, 0.2, 1.0),
+            PickupKind::Cargo(_) => Color::new(0.75, 0.55, 0.35, 1.0),
+            PickupKind::Ammo => Color::new(1.0, 0.45, 0.35, 1.0),
+            PickupKind::Repair => Color::new(0.4, 1.0, 0.5, 1.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Pickup {
+    pub kind: PickupKind,
+    pub amount: u32,
+    pub spawned: f64,
+    pub lifetime: f64,
+    /// Neither pulled in nor collected before this time.
+    pub collectable_after: f64,
+}
+
+impl Pickup {
+    pub fn new(kind: PickupKind, amount: u32) -> Self {
+        let lifetime = match kind {
+            PickupKind::Cargo(_) => CRATE_LIFETIME,
+            _ => LIFETIME,
+        };
+
+        Self {
+            kind,
+            amount,
+            spawned: get_time(),
+            lifetime,
+            collectable_after: f64::NEG_INFINITY,
+        }
+    }
+
+    pub fn is_collectable(&self, current_time: f64) -> bool {
+        current_time >= self.collectable_after
+    }
+
+    /// Cargo comes in crates, everything else in the usual pickup.
+    pub fn spawn(self, position: Vector<Real>, linvel: Vector<Real>) -> Option<WorldMutator> {
+        let resource = match self.kind {
+            PickupKind::Cargo(_) => &CRATE,
+            _ => &PICKUP,
+        };
+        let drawable = Drawable::from_resource(resource)?.tint(self.kind.color());
+
+        Some(
+            EntityBuilder::new(resource)
+                .drawable(drawable)
+                .pickup(self)
+                .build_mutator(Box::new(move |entity, rigid_body_set| {
+                    let rigid_body = entity.get_rigid_body_mut(rigid_body_set)?;
+
+                    rigid_body.set_position(Isometry::new(position, 0.0), false);
+                    rigid_body.set_linvel(linvel, true);
+
+                    None
+                })),
+        )
+    }
+}
+
+pub trait PickupLike {
+    /// A few random pickups, scattered from where the entity was.
+    fn drop_loot(&self, rigid_body_set: &RigidBodySet) -> Option<Vec<WorldMutator>>;
+
+    fn update_pickup(&mut self, current_time: f64) -> Option<WorldMutator>;
+}
+
+impl PickupLike for Entity {
+    fn drop_loot(&self, rigid_body_set: &RigidBodySet) -> Option<Vec<WorldMutator>> {
+        let rigid_body = self.get_rigid_body(rigid_body_set)?;
+        let position = *rigid_body.translation();
+        let linvel = *rigid_body.linvel();
+
+        let mutators = (0..rand::gen_range(DROPS.0, DROPS.1 + 1))
+            .filter_map(|_| {
+                let kind = PickupKind::roll();
+
+                let direction = UnitComplex::new(rand::gen_range(-PI, PI));
+                let scatter: Vector<Real> =
+                    direction * Vector::x() * rand::gen_range(SCATTER_SPEED.0, SCATTER_SPEED.1);
+
+                Pickup::new(kind, kind.roll_amount()).spawn(position, linvel + scatter)
+            })
+            .collect();
+
+        Some(mutators)
+    }
+
+    fn update_pickup(&mut self, current_time: f64) -> Option<WorldMutator> {
+        let pickup = self.pickup?;
+        let elapsed = current_time - pickup.spawned;
+
+        if elapsed > pickup.lifetime {
+            return Some(WorldMutator::Remove(self.entity_holder?));
+        }
+
+        let remaining = (pickup.lifetime - elapsed) / (pickup.lifetime * FADE_OUT);
+        if let Some(drawable) = self.drawable.as_mut() {
+            drawable.alpha = remaining.min(1.0) as f32;
+        }
+
+        None
+    }
+}
@@ -1,22 +1,55 @@
 use macroquad::miniquad::{KeyCode, MouseButton};
-use macroquad::prelude::{get_time, is_key_down, is_mouse_button_down};
+use macroquad::prelude::{
+    get_frame_time, get_time, is_key_down, is_key_pressed, is_mouse_button_down,
+};
 
-use nalgebra::{vector, Complex, ComplexField, Unit};
+use nalgebra::{point, Complex, Unit};
 use rapier2d::math::Real;
-use rapier2d::prelude::RigidBody;
+use rapier2d::prelude::{RigidBody, RigidBodySet};
 
 use crate::info::mouse::MouseInfo;
+use crate::util::screen::wrap_delta;
 use crate::world::world_mutator::WorldMutator;
 
 use super::{
-    entity::{Entity, EntityHolder},
-    projectile::projectile::ProjectileLike,
+    animation::{AnimationLike, AnimationState},
+    cargo::CargoLike,
+    docking::Docked,
+    entity::Entity,
+    entity::EntityHolder,
+    health::HealthLike,
+    hold::Hold,
+    inventory::InventoryLike,
+    reactor::System,
+    thrusters::{Thrust, ThrustersLike},
+    weapon::WeaponLike,
 };
 
 #[derive(Debug, Clone, Copy)]
 pub struct Player {
     pub mouse_info: MouseInfo,
-    pub last_fire_time: f64,
+    /// Next to a crippled ship, boarding it.
+    pub boarding: Option<Hold>,
+    /// Boarded and waiting for the player to decide what to do with it.
+    pub boarded: Option<EntityHolder>,
+    /// The station whose docking zone the player is in.
+    pub dock_zone: Option<EntityHolder>,
+    /// Holding still in `dock_zone`, docking.
+    pub docking: Option<Hold>,
+    pub docked: Option<Docked>,
+}
+
+impl Player {
+    pub fn new(mouse_info: MouseInfo) -> Self {
+        Self {
+            mouse_info,
+            boarding: None,
+            boarded: None,
+            dock_zone: None,
+            docking: None,
+            docked: None,
+        }
+    }
 }
 
 pub trait PlayerLike {
@@ -24,9 +57,12 @@ pub trait PlayerLike {
     fn mouse_info_mut(&mut self) -> Option<&mut MouseInfo>;
     fn angle_to_mouse(&self, rigid_body: &RigidBody) -> Option<Real>;
 
-    fn update_input(&self, rigid_body: &mut RigidBody) -> Option<()>;
+    fn update_input(&mut self, rigid_body: &mut RigidBody) -> Option<()>;
+
+    fn update_fire(&mut self) -> Option<Vec<WorldMutator>>;
 
-    fn update_fire(&mut self, player: &EntityHolder) -> Option<WorldMutator>;
+    /// J throws a crate overboard.
+    fn update_jettison(&mut self, rigid_body_set: &mut RigidBodySet) -> Option<WorldMutator>;
 }
 
 impl PlayerLike for Entity {
@@ -41,50 +77,106 @@ impl PlayerLike for Entity {
     fn angle_to_mouse(&self, rigid_body: &RigidBody) -> Option<Real> {
         let pos = rigid_body.translation();
         let mouse_pos = self.mouse_info()?.pos;
-        let mouse_pos = Complex::new(mouse_pos.x - pos.x, mouse_pos.y - pos.y);
+        let delta = wrap_delta(point!(pos.x, pos.y), point!(mouse_pos.x, mouse_pos.y));
+        let mouse_pos = Complex::new(delta.x, delta.y);
         let mouse_pos = Unit::from_complex(mouse_pos);
 
         Some(rigid_body.rotation().angle_to(&mouse_pos))
     }
 
-    fn update_input(&self, rigid_body: &mut RigidBody) -> Option<()> {
+    fn update_input(&mut self, rigid_body: &mut RigidBody) -> Option<()> {
         self.update_rotation(rigid_body);
-        self.update_velocity(rigid_body);
+        let thrusting = self.update_velocity(rigid_body)?;
+
+        if is_key_pressed(KeyCode::R) {
+            self.use_repair_kit();
+        }
+
+        self.update_pips();
+
+        let state = if self.is_damaged() {
+            AnimationState::Damaged
+        } else if thrusting {
+            AnimationState::Thrusting
+        } else {
+            AnimationState::Idle
+        };
+        self.set_animation_state(state, get_time());
 
         Some(())
     }
 
-    fn update_fire(&mut self, player: &EntityHolder) -> Option<WorldMutator> {
-        let ref mut last_fire_time = self.player.as_mut()?.last_fire_time;
+    fn update_fire(&mut self) -> Option<Vec<WorldMutator>> {
+        self.player.as_ref()?;
 
-        if is_mouse_button_down(MouseButton::Right) {
-            let current_time = get_time();
+        let trigger = is_mouse_button_down(MouseButton::Right);
+
+        self.update_weapons(trigger, get_time())
+    }
 
-            if (current_time - *last_fire_time) > 0.2 {
-                *last_fire_time = current_time;
+    fn update_jettison(&mut self, rigid_body_set: &mut RigidBodySet) -> Option<WorldMutator> {
+        self.player.as_ref()?;
 
-                return Some(Entity::spawn_projectile(*player, *self, 1.0)?);
-            }
+        if !is_key_pressed(KeyCode::J) {
+            return None;
         }
 
-        None
+        self.jettison(rigid_body_set)
     }
 }
 
-impl Entity {
-    fn update_velocity(&self, rigid_body: &mut RigidBody) -> Option<()> {
-        let mut velocity = *rigid_body.linvel();
+fn axis(negative: KeyCode, positive: KeyCode) -> Real {
+    let mut value = 0.0;
+
+    if is_key_down(negative) {
+        value -= 1.0;
+    }
+    if is_key_down(positive) {
+        value += 1.0;
+    }
+
+    value
+}
 
-        if is_key_down(KeyCode::S) {
-            velocity = velocity.scale(0.97);
+impl Entity {
+    /// W/S thrust and reverse, A/D strafe, shift burns. Returns whether the
+    /// ship is thrusting forward.
+    fn update_velocity(&mut self, rigid_body: &mut RigidBody) -> Option<bool> {
+        if is_key_pressed(KeyCode::F) {
+            let thrusters = self.thrusters.as_mut()?;
+            thrusters.flight_assist = !thrusters.flight_assist;
+
+            log::info!("flight assist {}", thrusters.flight_assist);
         }
-        if is_key_down(KeyCode::W) {
-            let d_v = rigid_body.rotation().scale(10.0);
-            let d_v = vector!(d_v.re, d_v.im);
 
-            velocity += d_v;
+        let thrust = Thrust {
+            forward: axis(KeyCode::S, KeyCode::W),
+            strafe: axis(KeyCode::A, KeyCode::D),
+            afterburner: is_key_down(KeyCode::LeftShift),
+        };
+
+        self.apply_thrust(thrust, rigid_body, get_frame_time())?;
+
+        Some(thrust.forward > 0.0)
+    }
+
+    /// Arrow keys shift reactor pips: left to shields, up to engines, right
+    /// to weapons, down balances them again.
+    fn update_pips(&mut self) -> Option<()> {
+        let reactor = self.reactor.as_mut()?;
+
+        if is_key_pressed(KeyCode::Left) {
+            reactor.add_pip(System::Shields);
+        }
+        if is_key_pressed(KeyCode::Up) {
+            reactor.add_pip(System::Engines);
+        }
+        if is_key_pressed(KeyCode::Right) {
+            reactor.add_pip(System::Weapons);
+        }
+        if is_key_pressed(KeyCode::Down) {
+            reactor.balance();
         }
-        rigid_body.set_linvel(velocity, true);
 
         Some(())
     }
@@ -92,17 +184,7 @@ impl Entity {
     fn update_rotation(&self, rigid_body: &mut RigidBody) -> Option<()> {
         let angle_to_mouse = self.angle_to_mouse(rigid_body)?;
 
-        let angvel = angle_to_mouse * 3.0;
-
-        /*
-        if is_key_down(KeyCode::D) {
-            rotation += 0.025;
-        }
-        if is_key_down(KeyCode::A) {
-            rotation -= 0.025;
-        }
-        */
-        rigid_body.set_angvel(angvel, true);
+        rigid_body.set_angvel(angle_to_mouse * 3.0, true);
 
         Some(())
     }
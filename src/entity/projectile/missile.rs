@@ -0,0 +1,100 @@
+use nalgebra::{vector, Complex, Unit, UnitComplex};
+use rapier2d::{
+    math::{Point, Real},
+    prelude::RigidBody,
+};
+
+use crate::{
+    entity::{entity::Entity, entity::EntityHolder},
+    util::screen::wrap_delta,
+};
+
+/// How a guided weapon acquires targets and how its missiles fly.
+#[derive(Debug, Clone, Copy)]
+pub struct Guidance {
+    /// Half angle in front of the shooter targets are acquired in.
+    pub cone: Real,
+    pub range: Real,
+    /// Seconds a target has to stay in the cone before the weapon may fire.
+    pub lock_time: f64,
+
+    /// Radians per second.
+    pub turn_rate: Real,
+    pub acceleration: Real,
+    pub max_speed: Real,
+    /// Seconds of powered, steered flight.
+    pub fuel: f64,
+
+    pub proximity: Real,
+    pub blast_radius: Real,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Homing {
+    pub guidance: Guidance,
+    pub target: EntityHolder,
+    pub fuel: f64,
+}
+
+impl Homing {
+    pub fn new(guidance: Guidance, target: EntityHolder) -> Self {
+        Self {
+            guidance,
+            target,
+            fuel: guidance.fuel,
+        }
+    }
+}
+
+pub trait MissileLike {
+    /// Steers toward `target` while there is fuel left, returns whether the
+    /// missile is close enough to detonate.
+    fn update_homing(
+        &mut self,
+        target: Option<Point<Real>>,
+        rigid_body: &mut RigidBody,
+        dt: f64,
+    ) -> Option<bool>;
+}
+
+impl MissileLike for Entity {
+    fn update_homing(
+        &mut self,
+        target: Option<Point<Real>>,
+        rigid_body: &mut RigidBody,
+        dt: f64,
+    ) -> Option<bool> {
+        let homing = self.projectile.as_mut()?.homing.as_mut()?;
+        let guidance = homing.guidance;
+
+        let pos = rigid_body.translation();
+        let pos = Point::new(pos.x, pos.y);
+        // without a target it just flies on
+        let delta = target.map(|v| wrap_delta(pos, v));
+
+        if homing.fuel <= 0.0 {
+            return Some(delta.is_some_and(|v| v.norm() < guidance.proximity));
+        }
+        homing.fuel -= dt;
+
+        let mut rotation = *rigid_body.rotation();
+
+        if let Some(delta) = delta {
+            let direction = Unit::from_complex(Complex::new(delta.x, delta.y));
+            let max_turn = guidance.turn_rate * dt as Real;
+            let turn = rotation.angle_to(&direction).clamp(-max_turn, max_turn);
+
+            rotation = UnitComplex::new(turn) * rotation;
+        }
+
+        let speed = (rigid_body.linvel().norm() + guidance.acceleration * dt as Real)
+            .min(guidance.max_speed);
+        let velocity = rotation.scale(speed);
+
+        rigid_body.set_rotation(rotation.angle(), true);
+        rigid_body.set_angvel(0.0, true);
+        rigid_body.set_linvel(vector!(velocity.re, velocity.im), true);
+
+        Some(delta.is_some_and(|v| v.norm() < guidance.proximity))
+    }
+}
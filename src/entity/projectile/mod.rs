@@ -1 +1,2 @@
+pub mod missile;
 pub mod projectile;
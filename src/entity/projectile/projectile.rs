@@ -1,47 +1,71 @@
 use macroquad::prelude::get_time;
-use nalgebra::vector;
-use rapier2d::{math::Isometry, prelude::RigidBodySet};
+use nalgebra::{vector, UnitComplex};
+use rapier2d::{
+    math::{Isometry, Point, Real},
+    prelude::{RigidBodyHandle, RigidBodySet},
+};
 
 use crate::{
     entity::{
         drawable::Drawable,
         entity::{Entity, EntityBuilder, EntityHolder},
         physics::PhysicsLike,
+        status::StatusEffect,
+        weapon::Weapon,
     },
     world::world_mutator::WorldMutator,
-    BULLET,
 };
 
+use super::missile::Homing;
+
+/// Fraction of the lifetime over which a projectile fades out.
+const FADE_OUT: f64 = 0.25;
+
 #[derive(Debug, Clone, Copy)]
 pub struct Projectile {
     pub source: EntityHolder,
     pub fired_time: f64,
     pub lifetime: f64,
+    pub damage: f32,
+    pub effect: Option<StatusEffect>,
+    pub homing: Option<Homing>,
+}
+
+/// Where and how a projectile leaves the ship that fired it.
+#[derive(Debug, Clone, Copy)]
+pub struct Muzzle {
+    /// In the local space of the source.
+    pub fire_point: Point<Real>,
+    pub speed: Real,
+    /// Added to the rotation of the source, e.g. for spread.
+    pub angle: Real,
 }
+
 pub trait ProjectileLike {
     fn spawn_projectile(
         source: EntityHolder,
-        source_entity: Entity,
-        lifetime: f64,
+        source_body: RigidBodyHandle,
+        weapon: &Weapon,
+        muzzle: Muzzle,
     ) -> Option<WorldMutator>;
 
-    fn update_projectile(&self, current_time: f64) -> Option<WorldMutator>;
+    fn update_projectile(&mut self, current_time: f64) -> Option<WorldMutator>;
 }
 
 fn set_projectile_physics(
     entity: &mut Entity,
-    source_entity: Entity,
+    source_body: RigidBodyHandle,
+    muzzle: Muzzle,
     rigid_body_set: &mut RigidBodySet,
 ) -> Option<()> {
-    let rotation = source_entity.rotation(&rigid_body_set)?;
-    let rotation_v = rotation.scale(800.0);
+    let source = rigid_body_set.get(source_body)?;
+
+    let rotation = source.rotation() * UnitComplex::new(muzzle.angle);
+    let rotation_v = rotation.scale(muzzle.speed);
 
-    let velocity = source_entity.velocity(&rigid_body_set)? + vector!(rotation_v.re, rotation_v.im);
+    let velocity = source.linvel() + vector!(rotation_v.re, rotation_v.im);
 
-    let fire_point = source_entity.resource.info.fire_points()?.get_point2s()[0];
-    let fire_point = source_entity
-        .transform(&rigid_body_set)?
-        .transform_point(&fire_point);
+    let fire_point = source.position().transform_point(&muzzle.fire_point);
 
     let rigid_body = entity.get_rigid_body_mut(rigid_body_set)?;
 
@@ -54,35 +78,48 @@ fn set_projectile_physics(
 impl ProjectileLike for Entity {
     fn spawn_projectile(
         source: EntityHolder,
-        source_entity: Entity,
-        lifetime: f64,
+        source_body: RigidBodyHandle,
+        weapon: &Weapon,
+        muzzle: Muzzle,
     ) -> Option<WorldMutator> {
-        let drawable = Drawable::from_resource(&BULLET)?;
+        let drawable = Drawable::from_resource(weapon.projectile)?;
         let projectile = Projectile {
             source,
             fired_time: get_time(),
-            lifetime,
+            lifetime: weapon.lifetime,
+            damage: weapon.damage,
+            effect: weapon.effect,
+            homing: weapon
+                .guidance
+                .zip(weapon.lock)
+                .map(|(guidance, lock)| Homing::new(guidance, lock.target)),
         };
 
         Some(
-            EntityBuilder::new(&BULLET)
+            EntityBuilder::new(weapon.projectile)
                 .drawable(drawable)
                 .projectile(projectile)
                 .build_mutator(Box::new(move |entity, rigid_body_set| {
-                    set_projectile_physics(entity, source_entity, rigid_body_set)?;
+                    set_projectile_physics(entity, source_body, muzzle, rigid_body_set)?;
                     None
                 })),
         )
     }
 
-    fn update_projectile(&self, current_time: f64) -> Option<WorldMutator> {
+    fn update_projectile(&mut self, current_time: f64) -> Option<WorldMutator> {
         let time_elapsed = current_time - self.projectile.as_ref()?.fired_time;
         let lifetime = self.projectile.as_ref()?.lifetime;
 
         if time_elapsed > lifetime {
             return Some(WorldMutator::Remove(self.entity_holder?));
         }
-        // todo add collision code
+
+        // fade out over the last part of the lifetime
+        let remaining = (lifetime - time_elapsed) / (lifetime * FADE_OUT);
+        if let Some(drawable) = self.drawable.as_mut() {
+            drawable.alpha = remaining.min(1.0) as f32;
+        }
+
         None
     }
 }
@@ -0,0 +1,39 @@
+use super::entity::Entity;
+
+/// Reinforced bow plating, turns a ship into something that wins collisions.
+#[derive(Debug, Clone, Copy)]
+pub struct RamPlating {
+    /// Multiplies the ram resistance of the resource.
+    pub resistance: f32,
+    /// Multiplies the collision damage dealt to the other ship.
+    pub damage: f32,
+}
+
+impl Default for RamPlating {
+    fn default() -> Self {
+        Self {
+            resistance: 3.0,
+            damage: 2.5,
+        }
+    }
+}
+
+pub trait RamLike {
+    /// Divides incoming collision damage.
+    fn ram_resistance(&self) -> f32;
+
+    /// Multiplies outgoing collision damage.
+    fn ram_damage(&self) -> f32;
+}
+
+impl RamLike for Entity {
+    fn ram_resistance(&self) -> f32 {
+        let base = self.resource.info.ram_resistance().unwrap_or(1.0);
+
+        base * self.ram_plating.map_or(1.0, |v| v.resistance)
+    }
+
+    fn ram_damage(&self) -> f32 {
+        self.ram_plating.map_or(1.0, |v| v.damage)
+    }
+}
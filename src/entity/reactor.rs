@@ -0,0 +1,231 @@
+use super::entity::Entity;
+
+/// Pips to spread over all systems.
+const TOTAL_PIPS: u8 = 6;
+pub const MAX_PIPS: u8 = 4;
+/// Performance of a system with no pips, every pip adds `PIP_FACTOR`.
+const BASE_FACTOR: f32 = 0.5;
+const PIP_FACTOR: f32 = 0.25;
+/// Below this the reactor counts as dry.
+const DRY_ENERGY: f32 = 1.0;
+/// Weapons cycle this much slower on a dry reactor.
+const DRY_COOLDOWN_FACTOR: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum System {
+    Weapons,
+    Shields,
+    Engines,
+}
+
+impl System {
+    pub const ALL: [System; 3] = [System::Weapons, System::Shields, System::Engines];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            System::Weapons => "WEP",
+            System::Shields => "SYS",
+            System::Engines => "ENG",
+        }
+    }
+}
+
+/// The energy pool weapons, shields and afterburners draw from, and how its
+/// output is split between them.
+#[derive(Debug, Clone, Copy)]
+pub struct Reactor {
+    pub energy: f32,
+    pub capacity: f32,
+    /// Energy per second.
+    pub output: f32,
+    /// Per system, they always add up to `TOTAL_PIPS`.
+    pub pips: [u8; 3],
+}
+
+impl Reactor {
+    pub fn new(capacity: f32, output: f32) -> Self {
+        Self {
+            energy: capacity,
+            capacity,
+            output,
+            pips: [TOTAL_PIPS / 3; 3],
+        }
+    }
+
+    pub fn pips(&self, system: System) -> u8 {
+        self.pips[system.index()]
+    }
+
+    /// Moves a pip to `system`, from whichever other system has the most.
+    pub fn add_pip(&mut self, system: System) {
+        if self.pips(system) >= MAX_PIPS {
+            return;
+        }
+
+        let donor = System::ALL
+            .into_iter()
+            .filter(|v| *v != system)
+            .max_by_key(|v| self.pips(*v));
+
+        if let Some(donor) = donor.filter(|v| self.pips(*v) > 0) {
+            self.pips[donor.index()] -= 1;
+            self.pips[system.index()] += 1;
+        }
+    }
+
+    pub fn balance(&mut self) {
+        self.pips = [TOTAL_PIPS / 3; 3];
+    }
+
+    /// How well `system` performs with its pips, 1 is the balanced default.
+    pub fn factor(&self, system: System) -> f32 {
+        BASE_FACTOR + PIP_FACTOR * self.pips(system) as f32
+    }
+
+    pub fn is_dry(&self) -> bool {
+        self.energy < DRY_ENERGY
+    }
+
+    /// Takes `amount` if it's there, otherwise takes nothing.
+    pub fn draw(&mut self, amount: f32) -> bool {
+        if self.energy < amount {
+            return false;
+        }
+
+        self.energy -= amount;
+
+        true
+    }
+
+    /// Takes what's there, up to `amount`. Returns the fraction it got.
+    pub fn drain(&mut self, amount: f32) -> f32 {
+        if amount <= 0.0 {
+            return 1.0;
+        }
+
+        let drained = self.energy.min(amount);
+        self.energy -= drained;
+
+        drained / amount
+    }
+
+    pub fn recharge(&mut self, dt: f32) {
+        self.energy = (self.energy + self.output * dt).min(self.capacity);
+    }
+
+    /// Scales weapon cooldowns, more weapon pips cycle faster and an empty
+    /// reactor cycles slower.
+    pub fn cooldown_scale(&self) -> f64 {
+        let dry = if self.is_dry() {
+            DRY_COOLDOWN_FACTOR
+        } else {
+            1.0
+        };
+
+        dry / self.factor(System::Weapons) as f64
+    }
+}
+
+pub trait ReactorLike {
+    fn update_reactor(&mut self, dt: f32) -> Option<()>;
+
+    /// Energy for special abilities. Ships without a reactor never run out.
+    fn draw_energy(&mut self, amount: f32) -> bool;
+
+    /// The performance factor of `system`, 1 without a reactor.
+    fn system_factor(&self, system: System) -> f32;
+}
+
+impl ReactorLike for Entity {
+    fn update_reactor(&mut self, dt: f32) -> Option<()> {
+        self.reactor.as_mut()?.recharge(dt);
+
+        Some(())
+    }
+
+    fn draw_energy(&mut self, amount: f32) -> bool {
+        self.reactor.as_mut().is_none_or(|v| v.draw(amount))
+    }
+
+    fn system_factor(&self, system: System) -> f32 {
+        self.reactor.map_or(1.0, |v| v.factor(system))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pips_come_from_the_fullest_other_system() {
+        let mut reactor = Reactor::new(100.0, 10.0);
+
+        reactor.add_pip(System::Weapons);
+        assert_eq!(reactor.pips.iter().sum::<u8>(), TOTAL_PIPS);
+        assert_eq!(reactor.pips(System::Weapons), 3);
+
+        reactor.add_pip(System::Weapons);
+        assert_eq!(reactor.pips, [4, 1, 1]);
+        assert_eq!(reactor.pips.iter().sum::<u8>(), TOTAL_PIPS);
+    }
+
+    #[test]
+    fn pips_stop_at_the_max() {
+        let mut reactor = Reactor::new(100.0, 10.0);
+
+        (0..10).for_each(|_| reactor.add_pip(System::Shields));
+
+        assert_eq!(reactor.pips(System::Shields), MAX_PIPS);
+        assert_eq!(reactor.pips.iter().sum::<u8>(), TOTAL_PIPS);
+
+        reactor.balance();
+        assert_eq!(reactor.pips, [2, 2, 2]);
+    }
+
+    #[test]
+    fn factor_is_one_when_balanced() {
+        let mut reactor = Reactor::new(100.0, 10.0);
+        assert_eq!(reactor.factor(System::Engines), 1.0);
+
+        (0..2).for_each(|_| reactor.add_pip(System::Engines));
+        assert_eq!(reactor.factor(System::Engines), 1.5);
+        assert!(reactor.factor(System::Weapons) < 1.0);
+    }
+
+    #[test]
+    fn draw_takes_all_or_nothing() {
+        let mut reactor = Reactor::new(10.0, 0.0);
+
+        assert!(reactor.draw(6.0));
+        assert!(!reactor.draw(6.0));
+        assert_eq!(reactor.energy, 4.0);
+    }
+
+    #[test]
+    fn drain_takes_what_is_there() {
+        let mut reactor = Reactor::new(10.0, 0.0);
+
+        assert_eq!(reactor.drain(4.0), 1.0);
+        assert_eq!(reactor.drain(12.0), 0.5);
+        assert_eq!(reactor.energy, 0.0);
+        assert!(reactor.is_dry());
+        assert_eq!(reactor.drain(0.0), 1.0);
+    }
+
+    #[test]
+    fn dry_reactors_cycle_weapons_slower() {
+        let mut reactor = Reactor::new(10.0, 5.0);
+        let full = reactor.cooldown_scale();
+
+        reactor.drain(10.0);
+        assert_eq!(reactor.cooldown_scale(), full * DRY_COOLDOWN_FACTOR);
+
+        reactor.recharge(1.0);
+        assert_eq!(reactor.energy, 5.0);
+        assert_eq!(reactor.cooldown_scale(), full);
+    }
+}
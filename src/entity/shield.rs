@@ -0,0 +1,179 @@
+use std::f32::consts::{FRAC_PI_4, PI};
+
+use macroquad::prelude::Color;
+use rapier2d::{
+    math::{Isometry, Point, Real},
+    prelude::RigidBodySet,
+};
+
+use crate::util::draw;
+
+use super::{entity::Entity, physics::PhysicsLike};
+
+const BUBBLE_COLOR: Color = Color::new(0.4, 0.8, 1.0, 1.0);
+/// Alpha of a full facing at rest, hits light it up on top of that.
+const BUBBLE_ALPHA: f32 = 0.25;
+const BUBBLE_HIT_DURATION: f64 = 0.3;
+/// Bubble radius relative to the larger half extent of the resource.
+const BUBBLE_SCALE: f32 = 1.15;
+const ARC_SEGMENTS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Facing {
+    Front,
+    Right,
+    Rear,
+    Left,
+}
+
+impl Facing {
+    pub const ALL: [Facing; 4] = [Facing::Front, Facing::Right, Facing::Rear, Facing::Left];
+
+    /// `angle` is relative to the nose, positive toward the right side.
+    pub fn from_angle(angle: Real) -> Facing {
+        if angle.abs() < FRAC_PI_4 {
+            Facing::Front
+        } else if angle.abs() > PI - FRAC_PI_4 {
+            Facing::Rear
+        } else if angle.is_sign_positive() {
+            Facing::Right
+        } else {
+            Facing::Left
+        }
+    }
+
+    /// The facing a world space `point` hits on a ship at `transform`.
+    pub fn from_impact(transform: &Isometry<Real>, point: Point<Real>) -> Facing {
+        let local = transform.inverse_transform_point(&point);
+
+        Facing::from_angle(local.y.atan2(local.x))
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Angle of the middle of the facing, relative to the nose.
+    fn center(self) -> Real {
+        self.index() as Real * PI / 2.0
+    }
+}
+
+/// Four independently depleting shield facings, which regenerate once the
+/// ship hasn't been hit for a while.
+#[derive(Debug, Clone, Copy)]
+pub struct Shields {
+    pub strength: [f32; 4],
+    pub max_strength: f32,
+    /// Per facing and second.
+    pub regen: f32,
+    pub regen_delay: f64,
+
+    pub last_hit: f64,
+    pub last_hit_facing: Option<Facing>,
+}
+
+impl Shields {
+    pub fn new(max_strength: f32, regen: f32, regen_delay: f64) -> Self {
+        Self {
+            strength: [max_strength; 4],
+            max_strength,
+            regen,
+            regen_delay,
+            last_hit: f64::NEG_INFINITY,
+            last_hit_facing: None,
+        }
+    }
+
+    /// Soaks up as much of `damage` as the facing can, returns the rest.
+    pub fn absorb(&mut self, facing: Facing, damage: f32, current_time: f64) -> f32 {
+        let strength = &mut self.strength[facing.index()];
+        let absorbed = strength.min(damage);
+        *strength -= absorbed;
+
+        self.last_hit = current_time;
+        self.last_hit_facing = Some(facing);
+
+        damage - absorbed
+    }
+
+    /// Takes `amount` off every facing, and holds off regeneration like a hit.
+    pub fn drain(&mut self, amount: f32, current_time: f64) {
+        self.strength
+            .iter_mut()
+            .for_each(|v| *v = (*v - amount).max(0.0));
+
+        self.last_hit = current_time;
+    }
+
+    /// Regenerates at `rate` times the normal speed, returns how much
+    /// strength came back over all facings.
+    pub fn regenerate(&mut self, current_time: f64, dt: f32, rate: f32) -> f32 {
+        if current_time - self.last_hit < self.regen_delay {
+            return 0.0;
+        }
+
+        let max_strength = self.max_strength;
+        let regen = self.regen * rate * dt;
+
+        self.strength
+            .iter_mut()
+            .map(|v| {
+                let before = *v;
+                *v = (*v + regen).min(max_strength);
+                *v - before
+            })
+            .sum()
+    }
+
+    pub fn facing(&self, facing: Facing) -> f32 {
+        self.strength[facing.index()]
+    }
+}
+
+pub trait ShieldLike {
+    fn draw_shield(&self, rigid_body_set: &RigidBodySet, current_time: f64) -> Option<()>;
+}
+
+impl ShieldLike for Entity {
+    /// One arc per facing, fading as it depletes and flaring up when hit.
+    fn draw_shield(&self, rigid_body_set: &RigidBodySet, current_time: f64) -> Option<()> {
+        let shields = self.health.as_ref()?.shields.as_ref()?;
+        let transform = self.transform(rigid_body_set)?;
+        let radius = self.resource.width.max(self.resource.height) / 2.0 * BUBBLE_SCALE;
+
+        let flare = (1.0 - (current_time - shields.last_hit) / BUBBLE_HIT_DURATION).max(0.0) as f32;
+
+        Facing::ALL.iter().for_each(|facing| {
+            let fill = shields.facing(*facing) / shields.max_strength;
+            if fill <= 0.0 {
+                return;
+            }
+
+            let mut alpha = BUBBLE_ALPHA * fill;
+            if shields.last_hit_facing == Some(*facing) {
+                alpha += (1.0 - BUBBLE_ALPHA) * flare;
+            }
+            let color = Color {
+                a: alpha,
+                ..BUBBLE_COLOR
+            };
+
+            let start = facing.center() - FRAC_PI_4;
+            let points = (0..=ARC_SEGMENTS)
+                .map(|i| {
+                    let angle = start + (PI / 2.0) * i as Real / ARC_SEGMENTS as Real;
+                    let local = Point::new(angle.cos() * radius, angle.sin() * radius);
+
+                    transform.transform_point(&local)
+                })
+                .collect::<Vec<_>>();
+
+            points.windows(2).for_each(|v| {
+                draw::draw_line(v[0].x, v[0].y, v[1].x, v[1].y, 2.0, color);
+            });
+        });
+
+        Some(())
+    }
+}
@@ -0,0 +1,9 @@
+use crate::{economy::market::MarketId, util::station::StationSpec};
+
+/// Makes an entity a station ships can dock at, see `World::update_docking`.
+#[derive(Debug, Clone, Copy)]
+pub struct Station {
+    pub spec: &'static StationSpec,
+    /// Its market in the world's economy, if it has one.
+    pub market: Option<MarketId>,
+}
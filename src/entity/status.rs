@@ -0,0 +1,322 @@
+use macroquad::prelude::Color;
+use rapier2d::prelude::RigidBodySet;
+
+use super::{
+    entity::{Entity, EntityHolder},
+    physics::PhysicsLike,
+};
+
+/// How strongly an effect color replaces the tint, pulsing around this.
+const TINT_STRENGTH: f32 = 0.5;
+const TINT_PULSE: f32 = 0.2;
+const TINT_PULSE_RATE: f64 = 8.0;
+/// Fraction of the velocity a full slow takes away per second.
+const SLOW_DRAG: f32 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EffectKind {
+    /// Disables steering and weapons.
+    Emp,
+    /// Drains shields, `strength` per facing and second.
+    Ion,
+    /// Damages the hull past the shields, `strength` per second.
+    Burn,
+    /// Drags the ship down, `strength` from 0 to 1.
+    Slow,
+}
+
+/// What happens when an effect hits a ship that already has it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stacking {
+    /// The timer restarts, the stronger of both applies.
+    Refresh,
+    /// Every application runs on its own timer and adds up, the oldest one
+    /// drops off past `max`.
+    Stack { max: usize },
+}
+
+impl EffectKind {
+    pub fn stacking(self) -> Stacking {
+        match self {
+            EffectKind::Emp | EffectKind::Slow => Stacking::Refresh,
+            EffectKind::Ion => Stacking::Stack { max: 3 },
+            EffectKind::Burn => Stacking::Stack { max: 5 },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EffectKind::Emp => "EMP",
+            EffectKind::Ion => "ION",
+            EffectKind::Burn => "BURN",
+            EffectKind::Slow => "SLOW",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            EffectKind::Emp => Color::new(0.7, 0.85, 1.0, 1.0),
+            EffectKind::Ion => Color::new(0.7, 0.45, 1.0, 1.0),
+            EffectKind::Burn => Color::new(1.0, 0.5, 0.15, 1.0),
+            EffectKind::Slow => Color::new(0.4, 1.0, 0.9, 1.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StatusEffect {
+    pub kind: EffectKind,
+    pub strength: f32,
+    pub duration: f64,
+    /// Set when it gets applied.
+    pub started: f64,
+    /// Who applied it, burns damage the ship in their name.
+    pub source: Option<EntityHolder>,
+}
+
+impl StatusEffect {
+    fn new(kind: EffectKind, strength: f32, duration: f64) -> Self {
+        Self {
+            kind,
+            strength,
+            duration,
+            started: f64::NEG_INFINITY,
+            source: None,
+        }
+    }
+
+    pub fn by(mut self, source: EntityHolder) -> Self {
+        self.source = Some(source);
+
+        return self;
+    }
+
+    pub fn emp(duration: f64) -> Self {
+        Self::new(EffectKind::Emp, 1.0, duration)
+    }
+
+    pub fn ion(drain: f32, duration: f64) -> Self {
+        Self::new(EffectKind::Ion, drain, duration)
+    }
+
+    pub fn burn(damage_per_second: f32, duration: f64) -> Self {
+        Self::new(EffectKind::Burn, damage_per_second, duration)
+    }
+
+    pub fn slow(strength: f32, duration: f64) -> Self {
+        Self::new(EffectKind::Slow, strength.clamp(0.0, 1.0), duration)
+    }
+
+    pub fn remaining(&self, current_time: f64) -> f64 {
+        (self.started + self.duration - current_time).max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StatusEffects {
+    pub active: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    pub fn apply(&mut self, effect: StatusEffect, current_time: f64) {
+        let effect = StatusEffect {
+            started: current_time,
+            ..effect
+        };
+
+        match effect.kind.stacking() {
+            Stacking::Refresh => match self.active.iter_mut().find(|v| v.kind == effect.kind) {
+                Some(existing) => {
+                    existing.strength = existing.strength.max(effect.strength);
+                    existing.duration = effect.duration.max(existing.remaining(current_time));
+                    existing.started = current_time;
+                }
+                None => self.active.push(effect),
+            },
+            Stacking::Stack { max } => {
+                self.active.push(effect);
+
+                while self.stacks(effect.kind) > max {
+                    let oldest = self
+                        .active
+                        .iter()
+                        .position(|v| v.kind == effect.kind)
+                        .expect("stack is not empty");
+                    self.active.remove(oldest);
+                }
+            }
+        }
+    }
+
+    pub fn has(&self, kind: EffectKind) -> bool {
+        self.active.iter().any(|v| v.kind == kind)
+    }
+
+    pub fn stacks(&self, kind: EffectKind) -> usize {
+        self.active.iter().filter(|v| v.kind == kind).count()
+    }
+
+    /// Summed over all stacks.
+    pub fn strength(&self, kind: EffectKind) -> f32 {
+        self.active
+            .iter()
+            .filter(|v| v.kind == kind)
+            .map(|v| v.strength)
+            .sum()
+    }
+
+    /// The longest any stack of `kind` still runs.
+    pub fn remaining(&self, kind: EffectKind, current_time: f64) -> f64 {
+        self.active
+            .iter()
+            .filter(|v| v.kind == kind)
+            .map(|v| v.remaining(current_time))
+            .fold(0.0, f64::max)
+    }
+
+    fn expire(&mut self, current_time: f64) {
+        self.active.retain(|v| v.remaining(current_time) > 0.0);
+    }
+
+    /// Pulls `tint` toward the color of the most recently applied effect.
+    pub fn tint(&self, tint: Color, current_time: f64) -> Color {
+        let latest = match self
+            .active
+            .iter()
+            .max_by(|a, b| a.started.total_cmp(&b.started))
+        {
+            Some(v) => v,
+            None => return tint,
+        };
+        let color = latest.kind.color();
+
+        let pulse = (current_time * TINT_PULSE_RATE).sin() as f32;
+        let t = TINT_STRENGTH + TINT_PULSE * pulse;
+
+        Color::new(
+            tint.r + (color.r - tint.r) * t,
+            tint.g + (color.g - tint.g) * t,
+            tint.b + (color.b - tint.b) * t,
+            tint.a,
+        )
+    }
+}
+
+pub trait StatusLike {
+    fn apply_effect(&mut self, effect: StatusEffect, current_time: f64);
+
+    /// Expires effects and applies ion drain and slow. Returns the burn damage
+    /// per second of every burning stack with who set it, which goes through
+    /// the world so it can destroy the ship.
+    fn update_status(
+        &mut self,
+        current_time: f64,
+        dt: f32,
+        rigid_body_set: &mut RigidBodySet,
+    ) -> Option<Vec<(f32, Option<EntityHolder>)>>;
+
+    /// Whether an EMP keeps it from steering and firing.
+    fn is_disabled(&self) -> bool;
+}
+
+impl StatusLike for Entity {
+    fn apply_effect(&mut self, effect: StatusEffect, current_time: f64) {
+        self.status_effects.apply(effect, current_time);
+    }
+
+    fn update_status(
+        &mut self,
+        current_time: f64,
+        dt: f32,
+        rigid_body_set: &mut RigidBodySet,
+    ) -> Option<Vec<(f32, Option<EntityHolder>)>> {
+        self.status_effects.expire(current_time);
+
+        if self.status_effects.active.is_empty() {
+            return None;
+        }
+
+        let drain = self.status_effects.strength(EffectKind::Ion) * dt;
+        if drain > 0.0 {
+            if let Some(shields) = self.health.as_mut().and_then(|v| v.shields.as_mut()) {
+                shields.drain(drain, current_time);
+            }
+        }
+
+        let slow = self.status_effects.strength(EffectKind::Slow).min(1.0);
+        if slow > 0.0 {
+            let rigid_body = self.get_rigid_body_mut(rigid_body_set)?;
+            let factor = (1.0 - slow * SLOW_DRAG * dt).max(0.0);
+            rigid_body.set_linvel(rigid_body.linvel() * factor, true);
+        }
+
+        let burns = self
+            .status_effects
+            .active
+            .iter()
+            .filter(|v| v.kind == EffectKind::Burn && v.strength > 0.0)
+            .map(|v| (v.strength, v.source))
+            .collect::<Vec<_>>();
+
+        (!burns.is_empty()).then_some(burns)
+    }
+
+    fn is_disabled(&self) -> bool {
+        self.status_effects.has(EffectKind::Emp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refresh_restarts_the_timer_and_keeps_the_stronger() {
+        let mut effects = StatusEffects::default();
+
+        effects.apply(StatusEffect::slow(0.6, 2.0), 0.0);
+        effects.apply(StatusEffect::slow(0.3, 2.0), 1.5);
+
+        assert_eq!(effects.stacks(EffectKind::Slow), 1);
+        assert_eq!(effects.strength(EffectKind::Slow), 0.6);
+        assert_eq!(effects.remaining(EffectKind::Slow, 1.5), 2.0);
+    }
+
+    #[test]
+    fn refresh_never_shortens_what_is_left() {
+        let mut effects = StatusEffects::default();
+
+        effects.apply(StatusEffect::emp(5.0), 0.0);
+        effects.apply(StatusEffect::emp(1.0), 1.0);
+
+        assert_eq!(effects.remaining(EffectKind::Emp, 1.0), 4.0);
+    }
+
+    #[test]
+    fn stacks_add_up_and_drop_the_oldest_past_the_max() {
+        let mut effects = StatusEffects::default();
+
+        for i in 0..4 {
+            effects.apply(StatusEffect::ion(1.0 + i as f32, 3.0), i as f64);
+        }
+
+        assert_eq!(effects.stacks(EffectKind::Ion), 3);
+        assert_eq!(effects.strength(EffectKind::Ion), 2.0 + 3.0 + 4.0);
+        assert_eq!(effects.remaining(EffectKind::Ion, 3.0), 3.0);
+    }
+
+    #[test]
+    fn stacks_expire_on_their_own_timers() {
+        let mut effects = StatusEffects::default();
+
+        effects.apply(StatusEffect::burn(2.0, 1.0), 0.0);
+        effects.apply(StatusEffect::burn(3.0, 1.0), 0.5);
+        effects.apply(StatusEffect::emp(0.2), 0.5);
+
+        effects.expire(1.2);
+
+        assert_eq!(effects.stacks(EffectKind::Burn), 1);
+        assert_eq!(effects.strength(EffectKind::Burn), 3.0);
+        assert!(!effects.has(EffectKind::Emp));
+    }
+}
@@ -0,0 +1,148 @@
+use rapier2d::{
+    math::{Real, Vector},
+    prelude::RigidBody,
+};
+
+use crate::util::module::{Module, ModuleStats};
+
+use super::{
+    entity::Entity,
+    reactor::{ReactorLike, System},
+};
+
+/// How hard the speed cap pulls back, per second of excess speed.
+const SPEED_CAP_STIFFNESS: Real = 4.0;
+
+/// Per-ship engine stats. Thrust is a force, so heavier ships accelerate
+/// slower on the same engines.
+#[derive(Debug, Clone, Copy)]
+pub struct Thrusters {
+    pub thrust: Real,
+    pub reverse_thrust: Real,
+    /// Sideways, on both sides.
+    pub strafe_thrust: Real,
+    pub max_speed: Real,
+
+    /// Multiplies the forward thrust while burning.
+    pub afterburner: Real,
+    pub afterburner_max_speed: Real,
+    /// Reactor energy per second of burning.
+    pub afterburner_cost: f32,
+
+    /// Damps sideways drift, so the ship flies where it points.
+    pub flight_assist: bool,
+    /// Fraction of the drift taken out per second.
+    pub assist_damping: Real,
+}
+
+impl Default for Thrusters {
+    fn default() -> Self {
+        Self {
+            thrust: 500000.0,
+            reverse_thrust: 250000.0,
+            strafe_thrust: 250000.0,
+            max_speed: 700.0,
+            afterburner: 2.0,
+            afterburner_max_speed: 1100.0,
+            afterburner_cost: 40.0,
+            flight_assist: true,
+            assist_damping: 2.0,
+        }
+    }
+}
+
+impl Thrusters {
+    /// The stats of an engine module, `None` for any other module.
+    pub fn from_module(module: &Module) -> Option<Thrusters> {
+        match module.stats {
+            ModuleStats::Engine {
+                thrust,
+                reverse_thrust,
+                strafe_thrust,
+                max_speed,
+                afterburner,
+                afterburner_max_speed,
+            } => Some(Thrusters {
+                thrust,
+                reverse_thrust,
+                strafe_thrust,
+                max_speed,
+                afterburner,
+                afterburner_max_speed,
+                ..Default::default()
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// What the pilot asks of the engines this frame.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Thrust {
+    /// From -1 for full reverse to 1 for full forward.
+    pub forward: Real,
+    /// From -1 for full left to 1 for full right.
+    pub strafe: Real,
+    pub afterburner: bool,
+}
+
+pub trait ThrustersLike {
+    /// Applies the forces for `thrust`, the speed cap and flight assist.
+    /// Thrust scales with the engine pips, the afterburner burns reactor
+    /// energy. Returns whether the afterburner is lit.
+    fn apply_thrust(&mut self, thrust: Thrust, rigid_body: &mut RigidBody, dt: f32)
+        -> Option<bool>;
+}
+
+impl ThrustersLike for Entity {
+    fn apply_thrust(
+        &mut self,
+        thrust: Thrust,
+        rigid_body: &mut RigidBody,
+        dt: f32,
+    ) -> Option<bool> {
+        let thrusters = self.thrusters?;
+        let pips = self.system_factor(System::Engines);
+
+        let burning = thrust.afterburner
+            && thrust.forward > 0.0
+            && self.draw_energy(thrusters.afterburner_cost * dt);
+
+        let forward: Vector<Real> = rigid_body.rotation() * Vector::x();
+        let right = Vector::new(-forward.y, forward.x);
+
+        let forward_thrust = if thrust.forward >= 0.0 {
+            let boost = if burning { thrusters.afterburner } else { 1.0 };
+            thrusters.thrust * boost
+        } else {
+            thrusters.reverse_thrust
+        };
+
+        let mut force = (forward * forward_thrust * thrust.forward
+            + right * thrusters.strafe_thrust * thrust.strafe)
+            * pips;
+
+        let mass = rigid_body.mass();
+        let velocity = *rigid_body.linvel();
+
+        if thrusters.flight_assist {
+            let drift = right * velocity.dot(&right);
+            force -= drift * mass * thrusters.assist_damping;
+        }
+
+        // a soft cap, so explosions and collisions can still push past it
+        let max_speed = if burning {
+            thrusters.afterburner_max_speed
+        } else {
+            thrusters.max_speed
+        };
+        let speed = velocity.norm();
+        if speed > max_speed {
+            force -= velocity / speed * (speed - max_speed) * mass * SPEED_CAP_STIFFNESS;
+        }
+
+        rigid_body.apply_force(force, true);
+
+        Some(burning)
+    }
+}
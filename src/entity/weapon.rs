@@ -0,0 +1,352 @@
+use macroquad::prelude::{get_frame_time, rand};
+use rapier2d::math::Real;
+
+use crate::{util::resource::Resource, world::world_mutator::WorldMutator, BEAM, BULLET, MISSILE};
+
+use super::{
+    beam::{Beam, BeamState},
+    entity::{Entity, EntityHolder},
+    health::HealthLike,
+    projectile::{
+        missile::Guidance,
+        projectile::{Muzzle, ProjectileLike},
+    },
+    status::{StatusEffect, StatusLike},
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Lock {
+    pub target: EntityHolder,
+    pub started: f64,
+}
+
+#[derive(Clone, Copy)]
+pub struct Weapon {
+    pub name: &'static str,
+
+    /// Seconds between the start of two bursts.
+    pub cooldown: f64,
+    pub muzzle_speed: Real,
+    /// Full angle of the cone shots are randomly spread over, in radians.
+    pub spread: Real,
+    /// Shots per trigger pull.
+    pub burst: u32,
+    pub burst_interval: f64,
+    /// Projectiles per shot, e.g. for scatter guns.
+    pub pellets: u32,
+
+    /// What gets spawned, or for beams what gets stretched along the ray.
+    pub projectile: &'static Resource,
+    pub lifetime: f64,
+    pub damage: f32,
+    /// Applied by every projectile that hits, beams don't carry effects.
+    pub effect: Option<StatusEffect>,
+
+    /// Index into the fire points of the ship's resource.
+    pub fire_point: usize,
+
+    /// Taken from the inventory per shot, ships without one never run out.
+    pub ammo_per_shot: u32,
+    /// Reactor energy per shot, or per second for beams.
+    pub energy: f32,
+
+    /// Guided weapons only fire once they have a lock.
+    pub guidance: Option<Guidance>,
+    pub lock: Option<Lock>,
+
+    /// Beams fire continuously instead of spawning projectiles.
+    pub beam: Option<Beam>,
+    pub beam_state: BeamState,
+
+    pub last_burst_time: f64,
+    pub last_shot_time: f64,
+    pub burst_left: u32,
+}
+
+impl Weapon {
+    fn new(name: &'static str, fire_point: usize) -> Self {
+        Self {
+            name,
+            cooldown: 0.2,
+            muzzle_speed: 800.0,
+            spread: 0.0,
+            burst: 1,
+            burst_interval: 0.0,
+            pellets: 1,
+            projectile: &BULLET,
+            lifetime: 1.0,
+            damage: 10.0,
+            effect: None,
+            fire_point,
+            ammo_per_shot: 0,
+            energy: 4.0,
+            guidance: None,
+            lock: None,
+            beam: None,
+            beam_state: BeamState::default(),
+            last_burst_time: f64::NEG_INFINITY,
+            last_shot_time: f64::NEG_INFINITY,
+            burst_left: 0,
+        }
+    }
+
+    pub fn blaster(fire_point: usize) -> Self {
+        Self::new("blaster", fire_point)
+    }
+
+    pub fn scatter(fire_point: usize) -> Self {
+        Self {
+            cooldown: 0.8,
+            muzzle_speed: 700.0,
+            spread: 0.35,
+            pellets: 5,
+            lifetime: 0.5,
+            damage: 4.0,
+            energy: 10.0,
+            ..Self::new("scatter", fire_point)
+        }
+    }
+
+    pub fn burst_cannon(fire_point: usize) -> Self {
+        Self {
+            cooldown: 1.0,
+            muzzle_speed: 900.0,
+            spread: 0.05,
+            burst: 3,
+            burst_interval: 0.08,
+            damage: 8.0,
+            energy: 6.0,
+            ..Self::new("burst cannon", fire_point)
+        }
+    }
+
+    pub fn missile_launcher(fire_point: usize) -> Self {
+        let guidance = Guidance {
+            cone: 0.5,
+            range: 1500.0,
+            lock_time: 1.2,
+            turn_rate: 2.5,
+            acceleration: 900.0,
+            max_speed: 700.0,
+            fuel: 3.0,
+            proximity: 40.0,
+            blast_radius: 90.0,
+        };
+
+        Self {
+            cooldown: 2.0,
+            muzzle_speed: 150.0,
+            projectile: &MISSILE,
+            lifetime: 5.0,
+            damage: 25.0,
+            ammo_per_shot: 1,
+            guidance: Some(guidance),
+            energy: 5.0,
+            ..Self::new("missile launcher", fire_point)
+        }
+    }
+
+    pub fn laser(fire_point: usize) -> Self {
+        let beam = Beam {
+            range: 700.0,
+            damage_per_second: 15.0,
+            max_ramp: 3.0,
+            ramp_time: 2.0,
+        };
+
+        Self {
+            projectile: &BEAM,
+            beam: Some(beam),
+            energy: 20.0,
+            ..Self::new("laser", fire_point)
+        }
+    }
+
+    pub fn ion_cannon(fire_point: usize) -> Self {
+        Self {
+            cooldown: 0.6,
+            muzzle_speed: 600.0,
+            damage: 3.0,
+            effect: Some(StatusEffect::ion(4.0, 3.0)),
+            energy: 8.0,
+            ..Self::new("ion cannon", fire_point)
+        }
+    }
+
+    pub fn incendiary(fire_point: usize) -> Self {
+        Self {
+            cooldown: 0.5,
+            damage: 4.0,
+            effect: Some(StatusEffect::burn(3.0, 2.5)),
+            energy: 6.0,
+            ..Self::new("incendiary", fire_point)
+        }
+    }
+
+    pub fn disruptor(fire_point: usize) -> Self {
+        Self {
+            cooldown: 2.5,
+            muzzle_speed: 500.0,
+            lifetime: 1.5,
+            damage: 2.0,
+            effect: Some(StatusEffect::emp(1.0)),
+            energy: 15.0,
+            ..Self::new("disruptor", fire_point)
+        }
+    }
+
+    pub fn snare(fire_point: usize) -> Self {
+        Self {
+            cooldown: 1.2,
+            muzzle_speed: 700.0,
+            damage: 2.0,
+            effect: Some(StatusEffect::slow(0.4, 2.0)),
+            energy: 8.0,
+            ..Self::new("snare", fire_point)
+        }
+    }
+
+    /// A preset by the name used in the module catalogue.
+    pub fn preset(name: &str, fire_point: usize) -> Option<Self> {
+        let weapon = match name {
+            "blaster" => Self::blaster(fire_point),
+            "scatter" => Self::scatter(fire_point),
+            "burst_cannon" => Self::burst_cannon(fire_point),
+            "missile_launcher" => Self::missile_launcher(fire_point),
+            "laser" => Self::laser(fire_point),
+            "ion_cannon" => Self::ion_cannon(fire_point),
+            "incendiary" => Self::incendiary(fire_point),
+            "disruptor" => Self::disruptor(fire_point),
+            "snare" => Self::snare(fire_point),
+            _ => {
+                log::warn!("unknown weapon preset {}", name);
+                return None;
+            }
+        };
+
+        Some(weapon)
+    }
+
+    /// From 0 when a target was just acquired to 1 once it's locked.
+    pub fn lock_progress(&self, current_time: f64) -> Option<f32> {
+        let lock_time = self.guidance?.lock_time;
+        let elapsed = current_time - self.lock?.started;
+
+        Some((elapsed / lock_time).clamp(0.0, 1.0) as f32)
+    }
+
+    fn is_locked(&self, current_time: f64) -> bool {
+        self.lock_progress(current_time).is_some_and(|v| v >= 1.0)
+    }
+
+    /// Keeps the lock while `target` stays the same, restarts it otherwise.
+    pub fn acquire(&mut self, target: Option<EntityHolder>, current_time: f64) {
+        self.lock = match (self.lock, target) {
+            (Some(lock), Some(target)) if lock.target == target => Some(lock),
+            (_, Some(target)) => Some(Lock {
+                target,
+                started: current_time,
+            }),
+            _ => None,
+        };
+    }
+
+    /// Advances the cooldown and burst state, returns whether a shot goes off now.
+    /// The cooldown is stretched by `cooldown_scale`.
+    pub fn trigger(&mut self, trigger: bool, current_time: f64, cooldown_scale: f64) -> bool {
+        if self.burst_left == 0 {
+            if !trigger || current_time - self.last_burst_time < self.cooldown * cooldown_scale {
+                return false;
+            }
+
+            self.burst_left = self.burst;
+            self.last_burst_time = current_time;
+        } else if current_time - self.last_shot_time < self.burst_interval {
+            return false;
+        }
+
+        self.burst_left -= 1;
+        self.last_shot_time = current_time;
+
+        true
+    }
+
+    fn spread_angle(&self) -> Real {
+        if self.spread <= 0.0 {
+            return 0.0;
+        }
+
+        rand::gen_range(-self.spread / 2.0, self.spread / 2.0)
+    }
+}
+
+pub trait WeaponLike {
+    fn update_weapons(&mut self, trigger: bool, current_time: f64) -> Option<Vec<WorldMutator>>;
+}
+
+impl WeaponLike for Entity {
+    fn update_weapons(&mut self, trigger: bool, current_time: f64) -> Option<Vec<WorldMutator>> {
+        let source = self.entity_holder?;
+        let source_body = self.physics?.rigid_body_handle;
+        let fire_points = self.resource.info.fire_points()?.get_point2s();
+
+        let mut mutators = vec![];
+        let trigger = trigger && !self.is_disabled() && !self.is_crippled();
+        let cooldown_scale = self.reactor.map_or(1.0, |v| v.cooldown_scale());
+        let dt = get_frame_time();
+
+        for weapon in self.weapons.iter_mut() {
+            // beams need the power to keep going, guns just cycle slower
+            if weapon.beam.is_some() {
+                weapon.beam_state.firing = trigger
+                    && self
+                        .reactor
+                        .as_mut()
+                        .is_none_or(|v| v.draw(weapon.energy * dt));
+                continue;
+            }
+
+            let locked = weapon.guidance.is_none() || weapon.is_locked(current_time);
+            let loaded = self
+                .inventory
+                .is_none_or(|v| v.ammo >= weapon.ammo_per_shot);
+            let ready = locked && loaded;
+
+            if !weapon.trigger(trigger && ready, current_time, cooldown_scale) {
+                continue;
+            }
+
+            let fire_point = match fire_points.get(weapon.fire_point) {
+                Some(v) => *v,
+                None => {
+                    log::warn!("{} is mounted on missing fire point", weapon.name);
+                    continue;
+                }
+            };
+
+            if let Some(inventory) = self.inventory.as_mut() {
+                inventory.ammo = inventory.ammo.saturating_sub(weapon.ammo_per_shot);
+            }
+            if let Some(reactor) = self.reactor.as_mut() {
+                reactor.drain(weapon.energy);
+            }
+
+            for _ in 0..weapon.pellets {
+                let muzzle = Muzzle {
+                    fire_point,
+                    speed: weapon.muzzle_speed,
+                    angle: weapon.spread_angle(),
+                };
+
+                mutators.extend(Entity::spawn_projectile(
+                    source,
+                    source_body,
+                    weapon,
+                    muzzle,
+                ));
+            }
+        }
+
+        Some(mutators)
+    }
+}
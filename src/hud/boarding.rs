@@ -0,0 +1,40 @@
+use macroquad::prelude::{get_time, GREEN, WHITE};
+
+use crate::{entity::boarding::BoardingChoice, util::screen::letterbox, world::world::World};
+
+use super::draw_centered_text;
+
+const BOARDING_SIZE: u16 = 32;
+const CHOICE_SIZE: u16 = 28;
+const CHOICE_SPACING: f32 = 34.0;
+/// From the center of the screen.
+const BOARDING_OFFSET: f32 = 120.0;
+
+/// On screen: boarding progress while holding position next to a crippled
+/// ship, and the choices once it's boarded.
+pub fn draw_boarding(world: &World) -> Option<()> {
+    let player = world.get_entity(&world.player?)?.player?;
+
+    let rect = letterbox();
+    let y = rect.y + rect.h / 2.0 + BOARDING_OFFSET;
+
+    if player.boarded.is_some() {
+        draw_centered_text("SHIP BOARDED", y, BOARDING_SIZE, GREEN);
+
+        BoardingChoice::ALL
+            .iter()
+            .enumerate()
+            .for_each(|(i, choice)| {
+                let y = y + CHOICE_SPACING * (i + 1) as f32;
+                draw_centered_text(choice.label(), y, CHOICE_SIZE, WHITE);
+            });
+
+        return Some(());
+    }
+
+    let progress = world.boarding_progress(get_time())?;
+    let text = format!("BOARDING {:.0}%", progress * 100.0);
+    draw_centered_text(&text, y, BOARDING_SIZE, WHITE);
+
+    Some(())
+}
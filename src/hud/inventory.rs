@@ -0,0 +1,34 @@
+use macroquad::prelude::{draw_text, measure_text, WHITE};
+
+use crate::{
+    hud::radar::{MARGIN, RADIUS},
+    util::screen::letterbox,
+    world::world::World,
+};
+
+const INVENTORY_SIZE: u16 = 24;
+const INVENTORY_MARGIN: f32 = 30.0;
+
+/// On screen: what the player carries, bottom right above the radar.
+pub fn draw_inventory(world: &World) -> Option<()> {
+    let player = world.get_entity(&world.player?)?;
+    let inventory = player.inventory?;
+    let (volume, capacity) = player
+        .cargo
+        .as_ref()
+        .map_or((0.0, 0.0), |v| (v.volume(), v.capacity));
+
+    let text = format!(
+        "CR {}  CARGO {:.0}/{:.0}  AMMO {}  KITS {}",
+        inventory.credits, volume, capacity, inventory.ammo, inventory.repair_kits
+    );
+
+    let rect = letterbox();
+    let size = measure_text(&text, None, INVENTORY_SIZE, 1.0);
+    let x = rect.x + rect.w - size.width - INVENTORY_MARGIN;
+    let y = rect.y + rect.h - 2.0 * (RADIUS + MARGIN) - INVENTORY_MARGIN;
+
+    draw_text(&text, x, y, INVENTORY_SIZE as f32, WHITE);
+
+    Some(())
+}
@@ -0,0 +1,111 @@
+use macroquad::prelude::{draw_text, get_time, measure_text, Color, ORANGE, RED, YELLOW};
+use rapier2d::prelude::RigidBodySet;
+
+use crate::{
+    entity::{entity::EntityHolder, physics::PhysicsLike},
+    util::{draw, screen::letterbox},
+    world::world::World,
+};
+
+const RETICLE_START: f32 = 90.0;
+const RETICLE_END: f32 = 45.0;
+const WARNING_SIZE: u16 = 40;
+const WARNING_MARGIN: f32 = 80.0;
+
+/// Every lock held by any weapon, as (shooter, target, progress).
+fn locks(world: &World, current_time: f64) -> Vec<(EntityHolder, EntityHolder, f32)> {
+    world
+        .entities
+        .iter()
+        .flat_map(|(holder, entity)| {
+            entity
+                .weapons
+                .iter()
+                .filter_map(move |v| Some((holder, v.lock?.target, v.lock_progress(current_time)?)))
+        })
+        .collect()
+}
+
+fn reticle(
+    world: &World,
+    rigid_body_set: &RigidBodySet,
+    on: EntityHolder,
+    progress: f32,
+    color: Color,
+) -> Option<()> {
+    let pos = world.get_entity(&on)?.pos(rigid_body_set)?;
+    let radius = RETICLE_START + (RETICLE_END - RETICLE_START) * progress;
+
+    draw::draw_circle_lines(pos.x, pos.y, radius, 2.0, color);
+
+    Some(())
+}
+
+/// In world space: a closing reticle on whatever the player is locking, and a
+/// marker on every ship locking the player.
+pub fn draw_lock_reticles(world: &World, rigid_body_set: &RigidBodySet) -> Option<()> {
+    let player = world.player?;
+
+    locks(world, get_time())
+        .into_iter()
+        .for_each(|(shooter, target, progress)| {
+            let locked = progress >= 1.0;
+
+            if shooter == player {
+                let color = if locked { RED } else { YELLOW };
+                reticle(world, rigid_body_set, target, progress, color);
+            } else if target == player {
+                let color = if locked { RED } else { ORANGE };
+                reticle(world, rigid_body_set, shooter, progress, color);
+            }
+        });
+
+    Some(())
+}
+
+/// On screen: warns the player while something is locking onto them, or a
+/// missile is already on its way.
+pub fn draw_lock_warning(world: &World) -> Option<()> {
+    let player = world.player?;
+    let current_time = get_time();
+
+    let incoming = world
+        .entities
+        .iter()
+        .filter_map(|(_, v)| v.projectile?.homing)
+        .any(|v| v.target == player);
+
+    let progress = locks(world, current_time)
+        .into_iter()
+        .filter(|v| v.1 == player)
+        .map(|v| v.2)
+        .fold(None, |acc: Option<f32>, v| {
+            Some(acc.map_or(v, |acc| acc.max(v)))
+        });
+
+    let (text, color) = match (incoming, progress) {
+        (true, _) => ("INCOMING MISSILE", RED),
+        (false, Some(v)) if v >= 1.0 => ("MISSILE LOCK", RED),
+        (false, Some(_)) => ("LOCKING", ORANGE),
+        (false, None) => return None,
+    };
+
+    // blink faster once locked
+    let rate = if progress.is_some_and(|v| v < 1.0) && !incoming {
+        4.0
+    } else {
+        8.0
+    };
+    if (current_time * rate) as i64 % 2 == 1 {
+        return Some(());
+    }
+
+    let rect = letterbox();
+    let size = measure_text(text, None, WARNING_SIZE, 1.0);
+    let x = rect.x + (rect.w - size.width) / 2.0;
+    let y = rect.y + WARNING_MARGIN;
+
+    draw_text(text, x, y, WARNING_SIZE as f32, color);
+
+    Some(())
+}
@@ -0,0 +1,21 @@
+use macroquad::prelude::{draw_text, measure_text, Color};
+
+use crate::util::screen::letterbox;
+
+pub mod boarding;
+pub mod inventory;
+pub mod lock;
+pub mod radar;
+pub mod reactor;
+pub mod reputation;
+pub mod station;
+pub mod status;
+
+/// Centered horizontally in the letterbox, with its baseline at `y`.
+fn draw_centered_text(text: &str, y: f32, size: u16, color: Color) {
+    let rect = letterbox();
+    let dimensions = measure_text(text, None, size, 1.0);
+    let x = rect.x + (rect.w - dimensions.width) / 2.0;
+
+    draw_text(text, x, y, size as f32, color);
+}
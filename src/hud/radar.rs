@@ -0,0 +1,152 @@
+use macroquad::{
+    camera::Camera2D,
+    prelude::{
+        draw_circle, draw_circle_lines, draw_line, draw_triangle, vec2, Color, Vec2, GRAY, GREEN,
+        RED, SKYBLUE, WHITE, YELLOW,
+    },
+};
+use rapier2d::math::{Point, Real};
+
+use crate::{
+    entity::{entity::Entity, physics::PhysicsLike},
+    util::{
+        collision::Collision,
+        screen::{letterbox, world_to_screen, wrap_delta},
+    },
+    world::world::World,
+};
+
+pub const RADIUS: f32 = 120.0;
+pub const MARGIN: f32 = 24.0;
+const ARROW_SIZE: f32 = 14.0;
+
+const BACKGROUND: Color = Color::new(0.0, 0.0, 0.0, 0.5);
+const RIM: Color = Color::new(0.6, 0.6, 0.6, 0.8);
+
+struct Blip {
+    /// Offset from the player, the short way around the world.
+    delta: Vec2,
+    color: Color,
+    size: f32,
+    /// Whether it gets an arrow on the edge of the screen while off-screen.
+    tracked: bool,
+}
+
+fn blip_style(entity: &Entity) -> (Color, f32, bool) {
+    if entity.player.is_some() {
+        return (GREEN, 4.0, false);
+    }
+
+    // fragments keep the resource, and so the group, of the ship they were
+    if entity.debris.is_some() {
+        return (GRAY, 2.0, false);
+    }
+
+    if let Some(station) = entity.station {
+        let color = entity.faction.map_or(WHITE, |v| v.color);
+        return (color, 6.0, station.market.is_some());
+    }
+
+    match entity.resource.info.collision_group() {
+        Some(groups) if Collision::is(groups, "ships") => (RED, 3.0, true),
+        Some(groups) if Collision::is(groups, "bullets") => (YELLOW, 1.5, false),
+        Some(groups) if Collision::is(groups, "pickups") => (SKYBLUE, 2.0, false),
+        _ => (GRAY, 2.0, false),
+    }
+}
+
+fn blips(world: &World, player_pos: Point<Real>, sensor_range: f32) -> Vec<Blip> {
+    let rigid_body_set = &*world.rigid_body_set.borrow();
+
+    world
+        .entities
+        .iter()
+        .filter_map(|(_, entity)| {
+            let delta = wrap_delta(player_pos, entity.pos(rigid_body_set)?);
+
+            if delta.norm() > sensor_range {
+                return None;
+            }
+
+            let (color, size, tracked) = blip_style(entity);
+
+            Some(Blip {
+                delta: vec2(delta.x, delta.y),
+                color,
+                size,
+                tracked,
+            })
+        })
+        .collect()
+}
+
+fn radar_center() -> Vec2 {
+    let rect = letterbox();
+
+    vec2(
+        rect.right() - RADIUS - MARGIN,
+        rect.bottom() - RADIUS - MARGIN,
+    )
+}
+
+fn draw_map(blips: &[Blip], sensor_range: f32) {
+    let center = radar_center();
+
+    draw_circle(center.x, center.y, RADIUS, BACKGROUND);
+    draw_circle_lines(center.x, center.y, RADIUS / 2.0, 1.0, RIM);
+    draw_circle_lines(center.x, center.y, RADIUS, 2.0, RIM);
+
+    blips.iter().for_each(|blip| {
+        let p = center + blip.delta * (RADIUS / sensor_range);
+
+        draw_circle(p.x, p.y, blip.size, blip.color);
+    });
+}
+
+/// Arrows along the edge of the view, pointing at tracked blips that are in
+/// sensor range but out of view.
+fn draw_edge_arrows(blips: &[Blip], player_pos: Point<Real>, camera: &Camera2D) {
+    let rect = letterbox();
+    let screen_center = rect.point() + rect.size() / 2.0;
+    let player_pos = vec2(player_pos.x, player_pos.y);
+
+    blips.iter().filter(|v| v.tracked).for_each(|blip| {
+        let on_screen = world_to_screen(camera, player_pos + blip.delta);
+
+        if rect.contains(on_screen) {
+            return;
+        }
+
+        let direction = (on_screen - screen_center).normalize_or_zero();
+        if direction == Vec2::ZERO {
+            return;
+        }
+
+        // scale the direction until it touches the (inset) edge of the screen
+        let half = rect.size() / 2.0 - vec2(MARGIN, MARGIN);
+        let scale = (half.x / direction.x.abs()).min(half.y / direction.y.abs());
+        let tip = screen_center + direction * scale;
+
+        let side = vec2(-direction.y, direction.x) * (ARROW_SIZE / 2.0);
+        let base = tip - direction * ARROW_SIZE;
+
+        draw_triangle(tip, base + side, base - side, blip.color);
+    });
+}
+
+pub fn draw_radar(world: &World, camera: &Camera2D, sensor_range: f32) -> Option<()> {
+    let player = world.get_entity(&world.player?)?;
+    let player_pos = player.pos(&world.rigid_body_set.borrow())?;
+
+    let blips = blips(world, player_pos, sensor_range);
+
+    draw_map(&blips, sensor_range);
+    draw_edge_arrows(&blips, player_pos, camera);
+
+    let heading = player.rotation(&world.rigid_body_set.borrow())?;
+    let center = radar_center();
+    let nose = center + vec2(heading.re, heading.im) * 12.0;
+    draw_line(center.x, center.y, nose.x, nose.y, 2.0, GREEN);
+
+    Some(())
+}
@@ -0,0 +1,65 @@
+use macroquad::prelude::{
+    draw_rectangle, draw_rectangle_lines, draw_text, Color, GRAY, ORANGE, RED,
+};
+
+use crate::{
+    entity::reactor::{System, MAX_PIPS},
+    util::screen::letterbox,
+    world::world::World,
+};
+
+const BAR_WIDTH: f32 = 240.0;
+const BAR_HEIGHT: f32 = 10.0;
+const BAR_MARGIN: f32 = 30.0;
+const PIP_SIZE: f32 = 12.0;
+const PIP_SPACING: f32 = 4.0;
+const LABEL_SIZE: f32 = 20.0;
+const COLUMN_WIDTH: f32 = BAR_WIDTH / 3.0;
+
+const ENERGY: Color = Color::new(0.3, 0.8, 1.0, 0.9);
+const EMPTY: Color = Color::new(1.0, 1.0, 1.0, 0.15);
+
+/// On screen: reactor energy and the pips per system, bottom center.
+pub fn draw_reactor(world: &World) -> Option<()> {
+    let reactor = world.get_entity(&world.player?)?.reactor?;
+
+    let rect = letterbox();
+    let x = rect.x + (rect.w - BAR_WIDTH) / 2.0;
+    let y = rect.y + rect.h - BAR_MARGIN - BAR_HEIGHT;
+
+    let fill = if reactor.capacity > 0.0 {
+        (reactor.energy / reactor.capacity).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let color = if reactor.is_dry() { RED } else { ENERGY };
+
+    draw_rectangle(x, y, BAR_WIDTH * fill, BAR_HEIGHT, color);
+    draw_rectangle_lines(x, y, BAR_WIDTH, BAR_HEIGHT, 1.0, GRAY);
+
+    System::ALL.iter().enumerate().for_each(|(column, system)| {
+        let column_x = x + column as f32 * COLUMN_WIDTH;
+        let pips_y = y - PIP_SPACING - PIP_SIZE;
+
+        (0..MAX_PIPS).for_each(|pip| {
+            let pip_color = if pip < reactor.pips(*system) {
+                ORANGE
+            } else {
+                EMPTY
+            };
+            let pip_x = column_x + pip as f32 * (PIP_SIZE + PIP_SPACING);
+
+            draw_rectangle(pip_x, pips_y, PIP_SIZE, PIP_SIZE, pip_color);
+        });
+
+        draw_text(
+            system.name(),
+            column_x,
+            pips_y - PIP_SPACING,
+            LABEL_SIZE,
+            GRAY,
+        );
+    });
+
+    Some(())
+}
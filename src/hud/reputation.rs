@@ -0,0 +1,28 @@
+use macroquad::prelude::{draw_text, measure_text};
+
+use crate::{util::screen::letterbox, world::world::World, FACTIONS};
+
+const REPUTATION_SIZE: u16 = 22;
+const REPUTATION_MARGIN: f32 = 30.0;
+const REPUTATION_SPACING: f32 = 26.0;
+
+/// On screen: how every faction stands with the player, top right.
+pub fn draw_reputation(world: &World) {
+    let rect = letterbox();
+    let mut y = rect.y + REPUTATION_MARGIN;
+
+    FACTIONS.factions.iter().for_each(|faction| {
+        let text = format!(
+            "{} {:+.0} {}",
+            faction.name,
+            world.reputation.get(faction),
+            world.reputation.standing(faction).name()
+        );
+
+        let size = measure_text(&text, None, REPUTATION_SIZE, 1.0);
+        let x = rect.x + rect.w - size.width - REPUTATION_MARGIN;
+
+        draw_text(&text, x, y, REPUTATION_SIZE as f32, faction.color);
+        y += REPUTATION_SPACING;
+    });
+}
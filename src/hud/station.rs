@@ -0,0 +1,121 @@
+use macroquad::prelude::{draw_rectangle, draw_text, get_time, Color, GRAY, RED, WHITE, YELLOW};
+
+use crate::{
+    entity::docking::Docked,
+    util::{screen::letterbox, station::Service},
+    world::world::World,
+};
+
+use super::draw_centered_text;
+
+const DOCKING_SIZE: u16 = 32;
+/// From the center of the screen.
+const DOCKING_OFFSET: f32 = 120.0;
+
+const PANEL_WIDTH: f32 = 760.0;
+const PANEL_MARGIN: f32 = 30.0;
+const PANEL_PADDING: f32 = 20.0;
+const PANEL_BACKGROUND: Color = Color::new(0.0, 0.0, 0.0, 0.75);
+const TITLE_SIZE: u16 = 30;
+const ROW_SIZE: u16 = 22;
+const ROW_SPACING: f32 = 26.0;
+const HELP: &str = "[UP/DOWN] select  [LEFT/RIGHT] browse  [B] buy  [N] sell  [U] undock";
+
+/// Station name, tabs, the rows of the open service and accepted contracts.
+fn draw_panel(world: &World, docked: Docked) -> Option<()> {
+    let station = world.get_entity(&docked.station)?;
+    let spec = station.station?.spec;
+
+    let mut lines: Vec<(String, u16, Color)> = vec![];
+
+    let title_color = station.faction.map_or(WHITE, |v| v.color);
+    lines.push((spec.name.to_uppercase(), TITLE_SIZE, title_color));
+
+    if let Some(faction) = station.faction {
+        let standing = world.reputation.standing(faction).name();
+        lines.push((format!("{} {}", faction.name, standing), ROW_SIZE, GRAY));
+    }
+
+    let tabs = Service::ALL
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| spec.offers(**v))
+        .map(|(i, v)| {
+            let marker = if *v == docked.service { "*" } else { " " };
+            format!("[{}]{}{}", i + 1, v.name(), marker)
+        })
+        .collect::<Vec<_>>()
+        .join("  ");
+    lines.push((tabs, ROW_SIZE, YELLOW));
+
+    world
+        .service_rows(docked)
+        .into_iter()
+        .enumerate()
+        .for_each(|(i, row)| {
+            let (marker, color) = if i == docked.selection {
+                ("> ", WHITE)
+            } else {
+                ("  ", GRAY)
+            };
+            lines.push((format!("{}{}", marker, row), ROW_SIZE, color));
+        });
+
+    if !world.contracts.is_empty() {
+        lines.push(("CONTRACTS".to_string(), ROW_SIZE, YELLOW));
+        world
+            .contracts
+            .iter()
+            .filter_map(|v| {
+                let left = (v.deadline - get_time()).max(0.0);
+                Some(format!(
+                    "{}, {:.0}s left",
+                    world.describe_contract(v)?,
+                    left
+                ))
+            })
+            .for_each(|v| lines.push((v, ROW_SIZE, GRAY)));
+    }
+
+    lines.push((HELP.to_string(), ROW_SIZE, GRAY));
+
+    let rect = letterbox();
+    let x = rect.x + PANEL_MARGIN;
+    let y = rect.y + PANEL_MARGIN;
+    let height = lines.len() as f32 * ROW_SPACING + PANEL_PADDING * 2.0;
+    draw_rectangle(x, y, PANEL_WIDTH, height, PANEL_BACKGROUND);
+
+    lines
+        .iter()
+        .enumerate()
+        .for_each(|(i, (text, size, color))| {
+            let line_y = y + PANEL_PADDING + ROW_SPACING * (i + 1) as f32;
+            draw_text(text, x + PANEL_PADDING, line_y, *size as f32, *color);
+        });
+
+    Some(())
+}
+
+/// On screen: docking progress while holding still in a docking zone, or the
+/// services of the station once docked.
+pub fn draw_station(world: &World) -> Option<()> {
+    let player = world.get_entity(&world.player?)?.player?;
+
+    if let Some(docked) = player.docked {
+        return draw_panel(world, docked);
+    }
+
+    let rect = letterbox();
+    let y = rect.y + rect.h / 2.0 + DOCKING_OFFSET;
+
+    if player.dock_zone.is_some_and(|v| world.is_refused(v)) {
+        draw_centered_text("DOCKING REFUSED", y, DOCKING_SIZE, RED);
+        return Some(());
+    }
+
+    let progress = world.docking_progress(get_time())?;
+    let text = format!("DOCKING {:.0}%", progress * 100.0);
+    draw_centered_text(&text, y, DOCKING_SIZE, WHITE);
+
+    Some(())
+}
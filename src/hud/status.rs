@@ -0,0 +1,52 @@
+use macroquad::prelude::{draw_text, get_time};
+
+use crate::{
+    entity::status::{EffectKind, StatusEffects},
+    util::screen::letterbox,
+    world::world::World,
+};
+
+const STATUS_SIZE: f32 = 24.0;
+const STATUS_MARGIN: f32 = 30.0;
+const STATUS_SPACING: f32 = 28.0;
+
+const KINDS: [EffectKind; 4] = [
+    EffectKind::Emp,
+    EffectKind::Ion,
+    EffectKind::Burn,
+    EffectKind::Slow,
+];
+
+fn label(effects: &StatusEffects, kind: EffectKind, current_time: f64) -> Option<String> {
+    let stacks = effects.stacks(kind);
+    if stacks == 0 {
+        return None;
+    }
+
+    let remaining = effects.remaining(kind, current_time);
+
+    Some(if stacks > 1 {
+        format!("{} x{} {:.1}s", kind.name(), stacks, remaining)
+    } else {
+        format!("{} {:.1}s", kind.name(), remaining)
+    })
+}
+
+/// On screen: the effects on the player, bottom left, with stacks and time left.
+pub fn draw_status_effects(world: &World) -> Option<()> {
+    let player = world.get_entity(&world.player?)?;
+    let current_time = get_time();
+
+    let rect = letterbox();
+    let mut y = rect.y + rect.h - STATUS_MARGIN;
+
+    KINDS
+        .iter()
+        .filter_map(|kind| Some((*kind, label(&player.status_effects, *kind, current_time)?)))
+        .for_each(|(kind, text)| {
+            draw_text(&text, rect.x + STATUS_MARGIN, y, STATUS_SIZE, kind.color());
+            y -= STATUS_SPACING;
+        });
+
+    Some(())
+}
@@ -3,7 +3,7 @@ use rapier2d::math::Isometry;
 
 use crate::{
     util::{
-        draw,
+        draw::{self, Modulation},
         screen::{self, world_center, TWO},
     },
     CURSOR,
@@ -28,7 +28,7 @@ impl MouseInfo {
         let (x, y) = self.pos.into();
         let (x, y) = (x - cursor.width / TWO, y - cursor.height / TWO);
 
-        draw::draw(cursor, Isometry::translation(x, y));
+        draw::draw(cursor, Isometry::translation(x, y), &Modulation::default());
     }
 
     pub fn from_mouse(&mut self, camera: &Camera2D) {
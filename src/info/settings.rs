@@ -0,0 +1,114 @@
+use macroquad::prelude::{is_key_down, is_key_pressed, KeyCode};
+
+use crate::{render::debug::DebugLayers, util::screen::set_fullscreen};
+
+#[derive(Debug, Clone, Copy)]
+pub struct PostSettings {
+    pub bloom: bool,
+    pub chromatic_aberration: bool,
+    pub vignette: bool,
+    pub scanlines: bool,
+}
+
+impl Default for PostSettings {
+    fn default() -> Self {
+        Self {
+            bloom: true,
+            chromatic_aberration: true,
+            vignette: true,
+            scanlines: false,
+        }
+    }
+}
+
+const RADAR_RANGE_MIN: f32 = 1000.0;
+const RADAR_RANGE_MAX: f32 = 6000.0;
+const RADAR_RANGE_STEP: f32 = 500.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    pub post: PostSettings,
+    pub debug: DebugLayers,
+    /// How far (in world units) the radar picks up entities.
+    pub radar_range: f32,
+    pub fullscreen: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            post: PostSettings::default(),
+            debug: DebugLayers::default(),
+            radar_range: 3000.0,
+            fullscreen: false,
+        }
+    }
+}
+
+fn toggle(key: KeyCode, name: &str, value: &mut bool) -> bool {
+    if is_key_pressed(key) {
+        *value = !*value;
+
+        log::info!("{} {}", name, if *value { "enabled" } else { "disabled" });
+
+        return true;
+    }
+
+    false
+}
+
+/// The command of the first of `keys` pressed this frame. Nothing counts
+/// while ` is down, that's for toggling debug layers.
+pub fn pressed<T: Copy>(keys: &[(KeyCode, T)]) -> Option<T> {
+    if is_key_down(KeyCode::GraveAccent) {
+        return None;
+    }
+
+    keys.iter()
+        .find(|(key, _)| is_key_pressed(*key))
+        .map(|v| v.1)
+}
+
+impl Settings {
+    pub fn update_from_input(&mut self) {
+        // debug layers are toggled with ` + a number, so they don't eat any
+        // of the regular keys
+        if is_key_down(KeyCode::GraveAccent) {
+            let debug = &mut self.debug;
+
+            toggle(KeyCode::Key1, "collider overlay", &mut debug.colliders);
+            toggle(KeyCode::Key2, "aabb overlay", &mut debug.aabbs);
+            toggle(KeyCode::Key3, "velocity overlay", &mut debug.velocities);
+            toggle(KeyCode::Key4, "fire point overlay", &mut debug.fire_points);
+            toggle(KeyCode::Key5, "contact overlay", &mut debug.contacts);
+            toggle(KeyCode::Key6, "sleeping overlay", &mut debug.sleeping);
+            toggle(KeyCode::Key7, "label overlay", &mut debug.labels);
+            toggle(KeyCode::Key8, "market overlay", &mut debug.markets);
+
+            return;
+        }
+
+        let post = &mut self.post;
+
+        toggle(KeyCode::F1, "bloom", &mut post.bloom);
+        toggle(
+            KeyCode::F2,
+            "chromatic aberration",
+            &mut post.chromatic_aberration,
+        );
+        toggle(KeyCode::F3, "vignette", &mut post.vignette);
+        toggle(KeyCode::F4, "scanlines", &mut post.scanlines);
+
+        if toggle(KeyCode::F11, "fullscreen", &mut self.fullscreen) {
+            set_fullscreen(self.fullscreen);
+        }
+
+        if is_key_pressed(KeyCode::Minus) {
+            self.radar_range -= RADAR_RANGE_STEP;
+        }
+        if is_key_pressed(KeyCode::Equal) {
+            self.radar_range += RADAR_RANGE_STEP;
+        }
+        self.radar_range = self.radar_range.clamp(RADAR_RANGE_MIN, RADAR_RANGE_MAX);
+    }
+}
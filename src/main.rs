@@ -1,14 +1,26 @@
+mod economy;
 mod entity;
+mod hud;
 mod info;
+mod render;
 
 #[macro_use]
 mod util;
 
 mod world;
 
-use entity::{drawable::Drawable, entity::EntityBuilder, player::Player};
+use economy::simulation::{load_economy, Economy};
+use entity::{
+    animation::Animation, drawable::Drawable, entity::EntityBuilder, inventory::Inventory,
+    loadout::Loadout, player::Player,
+};
 use futures::FutureExt;
-use info::mouse::MouseInfo;
+use hud::{
+    boarding::draw_boarding, inventory::draw_inventory, lock::draw_lock_warning, radar::draw_radar,
+    reactor::draw_reactor, reputation::draw_reputation, station::draw_station,
+    status::draw_status_effects,
+};
+use info::{mouse::MouseInfo, settings::Settings};
 use macroquad::prelude::*;
 use miniquad::conf::Conf;
 
@@ -17,27 +29,81 @@ use rapier2d::{
     crossbeam,
     na::vector,
     prelude::{
-        BroadPhase, CCDSolver, ChannelEventCollector, IntegrationParameters, IslandManager,
-        JointSet, NarrowPhase, PhysicsPipeline,
+        BroadPhase, CCDSolver, ChannelEventCollector, IntegrationParameters, PhysicsPipeline,
     },
 };
+use render::{
+    camera::CameraController,
+    debug::{draw_debug, draw_markets},
+    post::PostProcessor,
+};
 use util::{
+    faction::{load_factions, Factions},
+    module::{load_catalogue, Catalogue},
     resource::{self, Resource},
-    screen::{make_camera, world_max_coord, world_min_coord},
+    screen::{
+        letterbox, letterbox_viewport, screen_to_world, world_max_coord, world_min_coord,
+        VIEW_HEIGHT, VIEW_WIDTH,
+    },
+    station::{load_stations, Stations},
 };
-use world::world::World;
+use world::{event::GameEvent, world::World};
 
 pub const RESOURCE_SHIP: &str = "resources/ship.svg";
 pub const RESOURCE_BULLET: &str = "resources/bullet.svg";
+pub const RESOURCE_BEAM: &str = "resources/beam.svg";
+pub const RESOURCE_MISSILE: &str = "resources/missile.svg";
+pub const RESOURCE_PICKUP: &str = "resources/pickup.svg";
+pub const RESOURCE_CRATE: &str = "resources/crate.svg";
+pub const RESOURCE_STATION: &str = "resources/station.svg";
+pub const RESOURCE_MODULES: &str = "resources/modules.json";
+pub const RESOURCE_FACTIONS: &str = "resources/factions.json";
+pub const RESOURCE_COMMODITIES: &str = "resources/commodities.json";
+pub const RESOURCE_MARKETS: &str = "resources/markets.json";
+pub const RESOURCE_STATIONS: &str = "resources/stations.json";
 pub const RESOURCE_CURSOR: &str = "resources/cursor.svg";
 pub const RESOURCE_FONTS_IOSEVKA: &str = "resources/fonts/iosevka-term-slab-regular.ttf";
 
+const PLAYER_HULL: f32 = 200.0;
+/// Module ids, one per slot of the ship.
+const PLAYER_LOADOUT: &[&str] = &[
+    "laser",
+    "burst_cannon",
+    "burst_cannon",
+    "missile_launcher",
+    "engine_standard",
+    "reactor_standard",
+    "shield_standard",
+    "cargo_bay",
+    "cargo_bay",
+    "armor_plating",
+];
+const PLAYER_AMMO: u32 = 12;
+
 pub type ReLazy = Lazy<Resource>;
 
 pub static SHIP: ReLazy = load_resource_lazy!(RESOURCE_SHIP);
 pub static BULLET: ReLazy = load_resource_lazy!(RESOURCE_BULLET);
+pub static BEAM: ReLazy = load_resource_lazy!(RESOURCE_BEAM);
+pub static MISSILE: ReLazy = load_resource_lazy!(RESOURCE_MISSILE);
+pub static PICKUP: ReLazy = load_resource_lazy!(RESOURCE_PICKUP);
+pub static CRATE: ReLazy = load_resource_lazy!(RESOURCE_CRATE);
+pub static STATION: ReLazy = load_resource_lazy!(RESOURCE_STATION);
 pub static CURSOR: ReLazy = load_resource_lazy!(RESOURCE_CURSOR);
 
+pub static MODULES: Lazy<Catalogue> =
+    Lazy::new(|| load_catalogue(RESOURCE_MODULES).now_or_never().unwrap());
+pub static FACTIONS: Lazy<Factions> =
+    Lazy::new(|| load_factions(RESOURCE_FACTIONS).now_or_never().unwrap());
+pub static STATIONS: Lazy<Stations> =
+    Lazy::new(|| load_stations(RESOURCE_STATIONS).now_or_never().unwrap());
+/// The economy as it starts out, the world runs its own copy.
+pub static ECONOMY: Lazy<Economy> = Lazy::new(|| {
+    load_economy(RESOURCE_COMMODITIES, RESOURCE_MARKETS)
+        .now_or_never()
+        .unwrap()
+});
+
 pub static IOSEVKA: Lazy<Font> = Lazy::new(|| {
     load_ttf_font(RESOURCE_FONTS_IOSEVKA)
         .now_or_never()
@@ -50,10 +116,10 @@ fn config() -> Conf {
     let mut conf = Conf::default();
 
     conf.window_title = "Trade or Slay".to_string();
-    conf.window_width = 1920;
-    conf.window_height = 1080;
-    conf.window_resizable = false;
-    //conf.fullscreen = true;
+    conf.window_width = VIEW_WIDTH as i32;
+    conf.window_height = VIEW_HEIGHT as i32;
+    conf.window_resizable = true;
+    conf.fullscreen = std::env::args().any(|v| v == "--fullscreen");
 
     return conf;
 }
@@ -66,10 +132,12 @@ fn draw_info(world: &World, camera: &Camera2D) {
     draw_text_ex("UL", ul_x, ul_y, *TEXT_PARAM);
     draw_text_ex("DR", dr_x, dr_y, *TEXT_PARAM);
 
-    let (fps_x, fps_y) = camera.screen_to_world(vec2(20.0, 60.0)).into();
+    let corner = letterbox().point();
+
+    let (fps_x, fps_y) = screen_to_world(camera, corner + vec2(20.0, 60.0)).into();
     draw_text_ex(&get_fps().to_string(), fps_x, fps_y, *TEXT_PARAM);
 
-    let (entities_x, entities_y) = camera.screen_to_world(vec2(120.0, 60.0)).into();
+    let (entities_x, entities_y) = screen_to_world(camera, corner + vec2(120.0, 60.0)).into();
     draw_text_ex(
         &world.entities.len().to_string(),
         entities_x,
@@ -87,7 +155,10 @@ fn text_param() -> TextParams {
 }
 
 async fn load_resources() {
-    resolve_all!(IOSEVKA, SHIP, BULLET, CURSOR);
+    resolve_all!(
+        IOSEVKA, SHIP, BULLET, BEAM, MISSILE, PICKUP, CRATE, STATION, CURSOR, MODULES, FACTIONS,
+        ECONOMY, STATIONS
+    );
 }
 
 #[macroquad::main(config)]
@@ -103,18 +174,19 @@ async fn main() {
     {
         let drawable = Drawable::from_resource(&SHIP).unwrap();
 
-        let player = Player {
-            mouse_info: MouseInfo::default(),
-            last_fire_time: 0.0,
-        };
+        let player = Player::new(MouseInfo::default());
 
         let world_mutator = EntityBuilder::new(&SHIP)
             .drawable(drawable)
+            .animation(Animation::default())
             .player(player)
+            .inventory(Inventory::default().ammo(PLAYER_AMMO))
+            .loadout(Loadout::new(&SHIP, PLAYER_HULL).with_modules(&SHIP, &MODULES, PLAYER_LOADOUT))
             .build_no_postinit();
 
         // init
         world.set_player(world_mutator);
+        world.spawn_stations(&STATIONS);
     }
 
     /* Create other structures necessary for the simulation. */
@@ -122,24 +194,27 @@ async fn main() {
     let integration_parameters = IntegrationParameters::default();
     let mut physics_pipeline = PhysicsPipeline::new();
     let mut broad_phase = BroadPhase::new();
-    let mut narrow_phase = NarrowPhase::new();
     let mut ccd_solver = CCDSolver::new();
     let physics_hooks = ();
     let (contact_send, contact_recv) = crossbeam::channel::unbounded();
     let (intersection_send, intersection_recv) = crossbeam::channel::unbounded();
     let event_handler = ChannelEventCollector::new(intersection_send, contact_send);
 
-    let mut camera = make_camera();
+    let mut camera = CameraController::new();
+    let mut settings = Settings::default();
+    let mut post = PostProcessor::new();
 
     loop {
-        clear_background(BLANK);
+        settings.update_from_input();
+
+        post.begin(&mut camera.camera);
 
         physics_pipeline.step(
             &gravity,
             &integration_parameters,
             &mut *world.island_manager.borrow_mut(),
             &mut broad_phase,
-            &mut narrow_phase,
+            &mut *world.narrow_phase.borrow_mut(),
             &mut *world.rigid_body_set.borrow_mut(),
             &mut *world.collider_set.borrow_mut(),
             &mut *world.joint_set.borrow_mut(),
@@ -150,9 +225,47 @@ async fn main() {
 
         world.update(&contact_recv, &intersection_recv, &mut camera);
 
-        set_camera(&camera);
-
-        draw_info(&world, &camera);
+        set_camera(&camera.camera);
+
+        draw_info(&world, &camera.camera);
+
+        for event in world.events.drain(..) {
+            match event {
+                GameEvent::PlayerHit { strength } => {
+                    post.hit(strength);
+                    camera.add_trauma(strength);
+                }
+                GameEvent::PickedUp { kind, amount } => {
+                    log::info!("picked up {} {}", amount, kind.name());
+                }
+                GameEvent::ReputationChanged { faction, change } => {
+                    log::debug!("reputation with {} {:+.1}", faction.name, change);
+                }
+                GameEvent::Docked { station } => {
+                    log::info!("docked at {}", station.name);
+                }
+            }
+        }
+
+        post.end(&settings.post);
+
+        // the overlay is drawn straight to the screen, skipping post effects
+        let mut overlay_camera = camera.camera;
+        overlay_camera.render_target = None;
+        overlay_camera.viewport = Some(letterbox_viewport());
+        set_camera(&overlay_camera);
+        draw_debug(&world, &settings.debug);
+        set_default_camera();
+        draw_markets(&world, &settings.debug);
+
+        draw_radar(&world, &camera.camera, settings.radar_range);
+        draw_lock_warning(&world);
+        draw_status_effects(&world);
+        draw_boarding(&world);
+        draw_station(&world);
+        draw_inventory(&world);
+        draw_reactor(&world);
+        draw_reputation(&world);
 
         next_frame().await
     }
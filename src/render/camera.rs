@@ -0,0 +1,135 @@
+use macroquad::{
+    camera::Camera2D,
+    prelude::{get_time, mouse_wheel, vec2, Vec2},
+};
+use nalgebra::{point, vector};
+
+use crate::util::screen::{crop_to_world, make_camera, wrap_delta};
+
+/// Time it takes the camera to (roughly) catch up with its goal.
+const SMOOTH_TIME: f32 = 0.25;
+
+const VELOCITY_LOOK_AHEAD: f32 = 0.35;
+const MOUSE_LOOK_AHEAD: f32 = 0.25;
+const MAX_LOOK_AHEAD: f32 = 400.0;
+
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 2.0;
+const ZOOM_STEP: f32 = 1.1;
+const ZOOM_SPEED: f32 = 10.0;
+
+const TRAUMA_DECAY: f32 = 1.2;
+const MAX_SHAKE_OFFSET: f32 = 40.0;
+const MAX_SHAKE_ANGLE: f32 = 3.0;
+
+pub struct CameraController {
+    pub camera: Camera2D,
+    base_zoom: Vec2,
+
+    pos: Vec2,
+    vel: Vec2,
+
+    zoom: f32,
+    target_zoom: f32,
+
+    /// Shake amount in `0.0..=1.0`, the actual shake is its square.
+    trauma: f32,
+}
+
+/// Cheap smooth noise in `-1.0..=1.0`, good enough for shaking a camera.
+fn noise(t: f32, seed: f32) -> f32 {
+    ((t * 23.0 + seed).sin() + (t * 37.0 + seed * 1.7).sin() * 0.5) / 1.5
+}
+
+fn smooth_damp(delta: Vec2, vel: &mut Vec2, dt: f32) -> Vec2 {
+    let omega = 2.0 / SMOOTH_TIME;
+    let x = omega * dt;
+    let exp = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+
+    let temp = (*vel - delta * omega) * dt;
+    *vel = (*vel - temp * omega) * exp;
+
+    // offset from the goal after this step
+    (temp - delta) * exp
+}
+
+fn clamp_length(v: Vec2, max: f32) -> Vec2 {
+    let length = v.length();
+
+    if length > max {
+        v * (max / length)
+    } else {
+        v
+    }
+}
+
+impl CameraController {
+    pub fn new() -> Self {
+        let camera = make_camera();
+
+        Self {
+            camera,
+            base_zoom: camera.zoom,
+            pos: camera.target,
+            vel: Vec2::ZERO,
+            zoom: 1.0,
+            target_zoom: 1.0,
+            trauma: 0.0,
+        }
+    }
+
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+
+    pub fn update(&mut self, player_pos: Vec2, player_vel: Vec2, mouse_pos: Vec2, dt: f32) {
+        self.update_zoom(dt);
+        self.follow(player_pos, player_vel, mouse_pos, dt);
+
+        let shake = self.trauma * self.trauma;
+        let t = get_time() as f32;
+
+        let offset = vec2(noise(t, 0.0), noise(t, 10.0)) * MAX_SHAKE_OFFSET * shake;
+
+        self.camera.target = self.pos + offset / self.zoom;
+        self.camera.rotation = noise(t, 20.0) * MAX_SHAKE_ANGLE * shake;
+        self.camera.zoom = self.base_zoom * self.zoom;
+
+        self.trauma = (self.trauma - TRAUMA_DECAY * dt).max(0.0);
+    }
+
+    fn update_zoom(&mut self, dt: f32) {
+        let (_, wheel) = mouse_wheel();
+
+        if wheel > 0.0 {
+            self.target_zoom *= ZOOM_STEP;
+        } else if wheel < 0.0 {
+            self.target_zoom /= ZOOM_STEP;
+        }
+        self.target_zoom = self.target_zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+
+        let t = 1.0 - (-ZOOM_SPEED * dt).exp();
+        self.zoom += (self.target_zoom - self.zoom) * t;
+    }
+
+    /// Critically damped follow towards the player, looking ahead along its
+    /// velocity and towards the mouse. All offsets are taken the short way
+    /// around the world, so crossing the seam never makes the camera jump.
+    fn follow(&mut self, player_pos: Vec2, player_vel: Vec2, mouse_pos: Vec2, dt: f32) {
+        let player = point!(player_pos.x, player_pos.y);
+        let mouse = wrap_delta(player, point!(mouse_pos.x, mouse_pos.y));
+
+        let ahead = player_vel * VELOCITY_LOOK_AHEAD + vec2(mouse.x, mouse.y) * MOUSE_LOOK_AHEAD;
+        let ahead = clamp_length(ahead, MAX_LOOK_AHEAD);
+
+        let camera = point!(self.pos.x, self.pos.y);
+        let delta = wrap_delta(camera, player) + vector!(ahead.x, ahead.y);
+        let delta = vec2(delta.x, delta.y);
+
+        let offset = smooth_damp(delta, &mut self.vel, dt);
+        let pos = self.pos + delta + offset;
+
+        let pos = crop_to_world(point!(pos.x, pos.y));
+        self.pos = vec2(pos.x, pos.y);
+    }
+}
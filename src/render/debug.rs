@@ -0,0 +1,243 @@
+use macroquad::prelude::{
+    draw_text, vec2, Color, BLUE, GREEN, MAGENTA, ORANGE, RED, SKYBLUE, YELLOW,
+};
+use nalgebra::point;
+use rapier2d::{
+    math::{Isometry, Point, Real},
+    prelude::{Collider, ColliderSet, NarrowPhase, RigidBodySet},
+};
+
+use crate::{
+    entity::{entity::Entity, physics::PhysicsLike},
+    util::{draw, screen::letterbox},
+    world::world::World,
+};
+
+const VELOCITY_SCALE: f32 = 0.25;
+const NORMAL_LENGTH: f32 = 20.0;
+const LABEL_COLOR: Color = Color::new(0.8, 0.8, 0.8, 1.0);
+const MARKET_SIZE: f32 = 16.0;
+const MARKET_LINE: f32 = 18.0;
+const MARKET_COLUMN: f32 = 320.0;
+const MARKET_MARGIN: f32 = 30.0;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DebugLayers {
+    pub colliders: bool,
+    pub aabbs: bool,
+    pub velocities: bool,
+    pub fire_points: bool,
+    pub contacts: bool,
+    pub sleeping: bool,
+    pub labels: bool,
+    /// Prices and stock of every market, in screen space.
+    pub markets: bool,
+}
+
+impl DebugLayers {
+    pub fn any(&self) -> bool {
+        self.colliders
+            || self.aabbs
+            || self.velocities
+            || self.fire_points
+            || self.contacts
+            || self.sleeping
+            || self.labels
+    }
+}
+
+fn draw_polygon(points: &[Point<Real>], color: Color) {
+    let length = points.len();
+
+    points.iter().enumerate().for_each(|(idx, p1)| {
+        let p2 = points[(idx + 1) % length];
+        draw::draw_line(p1.x, p1.y, p2.x, p2.y, 1.0, color);
+    });
+}
+
+/// Outlines every convex sub-shape of a compound collider on its own.
+fn draw_collider(collider: &Collider) -> Option<()> {
+    let compound = collider.shape().as_compound()?;
+    let transform = collider.position();
+
+    compound.shapes().iter().for_each(|(local, shape)| {
+        if let Some(polygon) = shape.as_convex_polygon() {
+            let points = polygon
+                .points()
+                .iter()
+                .map(|v| transform.transform_point(&local.transform_point(v)))
+                .collect::<Vec<_>>();
+
+            draw_polygon(&points, RED);
+        }
+    });
+
+    Some(())
+}
+
+fn draw_aabb(collider: &Collider) {
+    let aabb = collider.compute_aabb();
+    let (min, max) = (aabb.mins, aabb.maxs);
+
+    draw_polygon(
+        &[min, point!(max.x, min.y), max, point!(min.x, max.y)],
+        ORANGE,
+    );
+}
+
+fn draw_fire_points(entity: &Entity, transform: &Isometry<Real>) -> Option<()> {
+    entity
+        .resource
+        .info
+        .fire_points()?
+        .get_point2s()
+        .iter()
+        .for_each(|p| {
+            let p = transform.transform_point(p);
+            draw::draw_circle_lines(p.x, p.y, 3.0, 1.0, YELLOW);
+        });
+
+    Some(())
+}
+
+fn label(entity: &Entity) -> Option<String> {
+    let (index, generation) = entity.entity_holder?.into_raw_parts();
+
+    let mut tags = vec![];
+    if entity.player.is_some() {
+        tags.push("player");
+    }
+    if entity.projectile.is_some() {
+        tags.push("projectile");
+    }
+
+    Some(format!("{}v{} {}", index, generation, tags.join(" ")))
+}
+
+fn draw_entity(
+    entity: &Entity,
+    layers: &DebugLayers,
+    rigid_body_set: &RigidBodySet,
+    collider_set: &ColliderSet,
+) -> Option<()> {
+    let rigid_body = entity.get_rigid_body(rigid_body_set)?;
+    let transform = rigid_body.position();
+    let pos = transform.translation;
+
+    rigid_body
+        .colliders()
+        .iter()
+        .filter_map(|v| collider_set.get(*v))
+        .for_each(|collider| {
+            if layers.colliders {
+                draw_collider(collider);
+            }
+            if layers.aabbs {
+                draw_aabb(collider);
+            }
+        });
+
+    if layers.velocities {
+        let end = rigid_body.linvel() * VELOCITY_SCALE;
+        draw::draw_line(pos.x, pos.y, pos.x + end.x, pos.y + end.y, 1.5, GREEN);
+    }
+
+    if layers.fire_points {
+        draw_fire_points(entity, transform);
+    }
+
+    if layers.sleeping && rigid_body.is_sleeping() {
+        draw::draw_circle_lines(pos.x, pos.y, 8.0, 2.0, SKYBLUE);
+    }
+
+    if layers.labels {
+        draw::draw_text(
+            &label(entity)?,
+            pos.x + 10.0,
+            pos.y - 10.0,
+            16.0,
+            LABEL_COLOR,
+        );
+    }
+
+    Some(())
+}
+
+fn draw_contacts(narrow_phase: &NarrowPhase) {
+    narrow_phase
+        .contact_pairs()
+        .filter(|v| v.has_any_active_contact)
+        .flat_map(|v| v.manifolds.iter())
+        .for_each(|manifold| {
+            let normal = manifold.data.normal * NORMAL_LENGTH;
+
+            manifold.data.solver_contacts.iter().for_each(|contact| {
+                let p = contact.point;
+
+                draw::draw_circle_lines(p.x, p.y, 2.0, 1.0, MAGENTA);
+                draw::draw_line(p.x, p.y, p.x + normal.x, p.y + normal.y, 1.0, BLUE);
+            });
+        });
+}
+
+/// Draws the enabled debug layers in world space, on top of the scene.
+pub fn draw_debug(world: &World, layers: &DebugLayers) {
+    if !layers.any() {
+        return;
+    }
+
+    let rigid_body_set = &*world.rigid_body_set.borrow();
+    let collider_set = &*world.collider_set.borrow();
+
+    world.entities.iter().for_each(|(_, entity)| {
+        draw_entity(entity, layers, rigid_body_set, collider_set);
+    });
+
+    if layers.contacts {
+        draw_contacts(&world.narrow_phase.borrow());
+    }
+}
+
+/// One column per market with the buy and sell price of a unit and the
+/// supply of everything it trades, in screen space.
+pub fn draw_markets(world: &World, layers: &DebugLayers) {
+    if !layers.markets {
+        return;
+    }
+
+    let economy = &world.economy;
+    let corner = letterbox().point() + vec2(MARKET_MARGIN, MARKET_MARGIN * 4.0);
+
+    economy
+        .markets
+        .iter()
+        .enumerate()
+        .for_each(|(market_id, market)| {
+            let x = corner.x + market_id as f32 * MARKET_COLUMN;
+            let mut y = corner.y;
+
+            draw_text(&market.name, x, y, MARKET_SIZE, LABEL_COLOR);
+
+            economy
+                .commodities
+                .iter()
+                .enumerate()
+                .for_each(|(commodity_id, commodity)| {
+                    let stock = match market.stock(commodity_id) {
+                        Some(v) => v,
+                        None => return,
+                    };
+                    let buy = economy.buy_price(market_id, commodity_id, 1).unwrap_or(0.0);
+                    let sell = economy
+                        .sell_price(market_id, commodity_id, 1)
+                        .unwrap_or(0.0);
+
+                    y += MARKET_LINE;
+                    let text = format!(
+                        "{:<12} {:>6.0} {:>6.0} {:>5.0}",
+                        commodity.name, buy, sell, stock.supply
+                    );
+                    draw_text(&text, x, y, MARKET_SIZE, LABEL_COLOR);
+                });
+        });
+}
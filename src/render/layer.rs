@@ -0,0 +1,37 @@
+/// Draw order of everything in the world, from back to front.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Layer {
+    Background,
+    Debris,
+    #[default]
+    Ships,
+    Projectiles,
+    Effects,
+    WorldUi,
+    Cursor,
+}
+
+impl Layer {
+    pub const ALL: [Layer; 7] = [
+        Layer::Background,
+        Layer::Debris,
+        Layer::Ships,
+        Layer::Projectiles,
+        Layer::Effects,
+        Layer::WorldUi,
+        Layer::Cursor,
+    ];
+
+    pub fn from_str(s: &str) -> Option<Layer> {
+        match s.to_lowercase().as_str() {
+            "background" => Some(Layer::Background),
+            "debris" => Some(Layer::Debris),
+            "ships" => Some(Layer::Ships),
+            "projectiles" => Some(Layer::Projectiles),
+            "effects" => Some(Layer::Effects),
+            "world_ui" => Some(Layer::WorldUi),
+            "cursor" => Some(Layer::Cursor),
+            _ => None,
+        }
+    }
+}
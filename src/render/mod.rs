@@ -0,0 +1,5 @@
+pub mod camera;
+pub mod debug;
+pub mod layer;
+pub mod post;
+pub mod shader;
@@ -0,0 +1,249 @@
+use macroquad::{
+    camera::{set_camera, set_default_camera, Camera2D},
+    material::{gl_use_default_material, gl_use_material, load_material, Material, MaterialParams},
+    prelude::{
+        clear_background, get_frame_time, get_time, render_target, vec2, Rect, RenderTarget,
+        Texture2D, UniformType, BLACK, BLANK, WHITE,
+    },
+    texture::{draw_texture_ex, DrawTextureParams},
+};
+
+use crate::{info::settings::PostSettings, util::screen::letterbox};
+
+use super::shader;
+
+const BLOOM_THRESHOLD: f32 = 0.6;
+const BLOOM_INTENSITY: f32 = 1.4;
+const VIGNETTE_RADIUS: f32 = 0.75;
+const VIGNETTE_SOFTNESS: f32 = 0.45;
+const ABERRATION_DECAY: f32 = 2.5;
+const ABERRATION_MAX: f32 = 1.0;
+
+struct Effects {
+    bloom: Material,
+    chromatic_aberration: Material,
+    vignette: Material,
+    scanlines: Material,
+}
+
+pub struct PostProcessor {
+    scene: RenderTarget,
+    ping: RenderTarget,
+    pong: RenderTarget,
+    size: (u32, u32),
+
+    effects: Effects,
+
+    /// Strength of the on-hit chromatic aberration, decays back to zero.
+    aberration: f32,
+}
+
+fn material(fragment: &str, uniforms: &[(&str, UniformType)]) -> Material {
+    let uniforms = uniforms
+        .iter()
+        .map(|(name, kind)| (name.to_string(), *kind))
+        .collect();
+
+    load_material(
+        shader::VERTEX,
+        fragment,
+        MaterialParams {
+            uniforms,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn effects() -> Effects {
+    Effects {
+        bloom: material(
+            shader::BLOOM,
+            &[
+                ("texel", UniformType::Float2),
+                ("threshold", UniformType::Float1),
+                ("intensity", UniformType::Float1),
+            ],
+        ),
+        chromatic_aberration: material(
+            shader::CHROMATIC_ABERRATION,
+            &[
+                ("texel", UniformType::Float2),
+                ("strength", UniformType::Float1),
+            ],
+        ),
+        vignette: material(
+            shader::VIGNETTE,
+            &[
+                ("radius", UniformType::Float1),
+                ("softness", UniformType::Float1),
+            ],
+        ),
+        scanlines: material(
+            shader::SCANLINES,
+            &[
+                ("resolution", UniformType::Float2),
+                ("time", UniformType::Float1),
+            ],
+        ),
+    }
+}
+
+/// Render targets match the letterboxed part of the window, in pixels.
+fn target_size() -> (u32, u32) {
+    let rect = letterbox();
+
+    (rect.w.max(1.0) as u32, rect.h.max(1.0) as u32)
+}
+
+/// Draws `source` over the whole of `target`, or the letterbox on screen if
+/// there is none.
+///
+/// Render target textures come out upside down, every pass flips them back.
+fn blit(source: Texture2D, target: Option<RenderTarget>, material: Option<Material>) {
+    let rect = match target {
+        Some(target) => Rect::new(0.0, 0.0, target.texture.width(), target.texture.height()),
+        None => letterbox(),
+    };
+
+    match target {
+        Some(target) => set_camera(&Camera2D {
+            render_target: Some(target),
+            ..Camera2D::from_display_rect(rect)
+        }),
+        None => set_default_camera(),
+    }
+
+    clear_background(BLACK);
+
+    if let Some(material) = material {
+        gl_use_material(material);
+    }
+
+    draw_texture_ex(
+        source,
+        rect.x,
+        rect.y,
+        WHITE,
+        DrawTextureParams {
+            dest_size: Some(vec2(rect.w, rect.h)),
+            flip_y: true,
+            ..Default::default()
+        },
+    );
+
+    gl_use_default_material();
+}
+
+impl PostProcessor {
+    pub fn new() -> Self {
+        let size = target_size();
+
+        Self {
+            scene: render_target(size.0, size.1),
+            ping: render_target(size.0, size.1),
+            pong: render_target(size.0, size.1),
+            size,
+            effects: effects(),
+            aberration: 0.0,
+        }
+    }
+
+    /// Recreates the render targets if the window was resized.
+    fn resize(&mut self) {
+        let size = target_size();
+
+        if size == self.size {
+            return;
+        }
+
+        [self.scene, self.ping, self.pong]
+            .iter()
+            .for_each(|target| target.delete());
+
+        self.scene = render_target(size.0, size.1);
+        self.ping = render_target(size.0, size.1);
+        self.pong = render_target(size.0, size.1);
+        self.size = size;
+    }
+
+    /// Flashes the chromatic aberration, e.g. when the player gets hit.
+    pub fn hit(&mut self, strength: f32) {
+        self.aberration = (self.aberration + strength).min(ABERRATION_MAX);
+    }
+
+    /// Redirects everything drawn through `camera` into the offscreen scene.
+    pub fn begin(&mut self, camera: &mut Camera2D) {
+        self.resize();
+
+        camera.render_target = Some(self.scene);
+        camera.viewport = None;
+
+        set_camera(camera);
+        clear_background(BLANK);
+    }
+
+    fn chain(&self, settings: &PostSettings) -> Vec<Material> {
+        let effects = &self.effects;
+        let (w, h) = (self.size.0 as f32, self.size.1 as f32);
+        let texel = vec2(1.0 / w, 1.0 / h);
+
+        let mut chain = vec![];
+
+        if settings.bloom {
+            effects.bloom.set_uniform("texel", texel);
+            effects.bloom.set_uniform("threshold", BLOOM_THRESHOLD);
+            effects.bloom.set_uniform("intensity", BLOOM_INTENSITY);
+
+            chain.push(effects.bloom);
+        }
+        if settings.chromatic_aberration && self.aberration > 0.0 {
+            let material = effects.chromatic_aberration;
+            material.set_uniform("texel", texel);
+            material.set_uniform("strength", self.aberration);
+
+            chain.push(material);
+        }
+        if settings.vignette {
+            effects.vignette.set_uniform("radius", VIGNETTE_RADIUS);
+            effects.vignette.set_uniform("softness", VIGNETTE_SOFTNESS);
+
+            chain.push(effects.vignette);
+        }
+        if settings.scanlines {
+            effects.scanlines.set_uniform("resolution", vec2(w, h));
+            effects.scanlines.set_uniform("time", get_time() as f32);
+
+            chain.push(effects.scanlines);
+        }
+
+        chain
+    }
+
+    /// Runs the scene through every enabled effect and presents it on screen.
+    pub fn end(&mut self, settings: &PostSettings) {
+        let chain = self.chain(settings);
+
+        let mut source = self.scene.texture;
+        let mut targets = [self.ping, self.pong].into_iter().cycle();
+
+        let last = chain.len().saturating_sub(1);
+
+        for (i, material) in chain.iter().enumerate() {
+            if i == last {
+                blit(source, None, Some(*material));
+            } else {
+                let target = targets.next().unwrap();
+
+                blit(source, Some(target), Some(*material));
+                source = target.texture;
+            }
+        }
+
+        if chain.is_empty() {
+            blit(source, None, None);
+        }
+
+        self.aberration = (self.aberration - get_frame_time() * ABERRATION_DECAY).max(0.0);
+    }
+}
@@ -0,0 +1,105 @@
+pub const VERTEX: &str = r#"#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+
+varying lowp vec2 uv;
+varying lowp vec4 color;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    color = color0 / 255.0;
+    uv = texcoord;
+}
+"#;
+
+pub const BLOOM: &str = r#"#version 100
+precision mediump float;
+
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 texel;
+uniform float threshold;
+uniform float intensity;
+
+void main() {
+    vec4 base = texture2D(Texture, uv);
+    vec3 glow = vec3(0.0);
+    float total = 0.0;
+
+    for (int x = -3; x <= 3; x++) {
+        for (int y = -3; y <= 3; y++) {
+            vec2 offset = vec2(float(x), float(y)) * texel * 2.0;
+            vec3 tap = texture2D(Texture, uv + offset).rgb;
+            float brightness = max(tap.r, max(tap.g, tap.b));
+            float weight = 1.0 / (1.0 + float(x * x + y * y));
+
+            glow += tap * step(threshold, brightness) * weight;
+            total += weight;
+        }
+    }
+
+    gl_FragColor = vec4(base.rgb + glow / total * intensity, 1.0);
+}
+"#;
+
+pub const CHROMATIC_ABERRATION: &str = r#"#version 100
+precision mediump float;
+
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 texel;
+uniform float strength;
+
+void main() {
+    vec2 direction = uv - vec2(0.5);
+    vec2 offset = direction * length(direction) * strength * texel * 64.0;
+
+    float r = texture2D(Texture, uv + offset).r;
+    float g = texture2D(Texture, uv).g;
+    float b = texture2D(Texture, uv - offset).b;
+
+    gl_FragColor = vec4(r, g, b, 1.0);
+}
+"#;
+
+pub const VIGNETTE: &str = r#"#version 100
+precision mediump float;
+
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+uniform float radius;
+uniform float softness;
+
+void main() {
+    vec4 base = texture2D(Texture, uv);
+    float dist = distance(uv, vec2(0.5));
+    float shade = smoothstep(radius, radius - softness, dist);
+
+    gl_FragColor = vec4(base.rgb * shade, 1.0);
+}
+"#;
+
+pub const SCANLINES: &str = r#"#version 100
+precision mediump float;
+
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 resolution;
+uniform float time;
+
+void main() {
+    vec4 base = texture2D(Texture, uv);
+    float line = sin((uv.y * resolution.y + time * 30.0) * 3.14159);
+    float shade = 0.85 + 0.15 * line;
+
+    gl_FragColor = vec4(base.rgb * shade, 1.0);
+}
+"#;
@@ -0,0 +1,99 @@
+use json::JsonValue;
+use macroquad::models::Mesh;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayMode {
+    Loop,
+    /// Plays once, then holds the last frame.
+    Once,
+}
+
+impl PlayMode {
+    pub fn from_str(s: &str) -> PlayMode {
+        match s.to_lowercase().as_str() {
+            "once" => PlayMode::Once,
+            _ => PlayMode::Loop,
+        }
+    }
+}
+
+/// A clip as declared in the resource attributes, before its frames are
+/// loaded.
+///
+/// Frames are either the id of an element (usually a group) in the same SVG,
+/// or the path to a separate SVG file.
+#[derive(Clone, Debug)]
+pub struct ClipSpec {
+    pub frames: Vec<String>,
+    pub durations: Vec<f64>,
+    pub mode: PlayMode,
+}
+
+impl ClipSpec {
+    pub fn from_json(value: &JsonValue) -> Option<ClipSpec> {
+        let frames = value["frames"]
+            .members()
+            .filter_map(|v| Some(v.as_str()?.to_string()))
+            .collect::<Vec<_>>();
+
+        if frames.is_empty() {
+            return None;
+        }
+
+        // either one duration per frame, or one for all of them
+        let durations = if value["durations"].is_array() {
+            value["durations"]
+                .members()
+                .filter_map(|v| v.as_f64())
+                .collect()
+        } else {
+            vec![value["duration"].as_f64().unwrap_or(0.1); frames.len()]
+        };
+
+        let mode = PlayMode::from_str(value["mode"].as_str().unwrap_or("loop"));
+
+        Some(ClipSpec {
+            frames,
+            durations,
+            mode,
+        })
+    }
+
+    pub fn is_file(frame: &str) -> bool {
+        frame.ends_with(".svg")
+    }
+}
+
+pub struct Frame {
+    pub mesh: Mesh,
+    pub duration: f64,
+}
+
+pub struct Clip {
+    pub frames: Vec<Frame>,
+    pub mode: PlayMode,
+}
+
+impl Clip {
+    pub fn length(&self) -> f64 {
+        self.frames.iter().map(|v| v.duration).sum()
+    }
+
+    pub fn frame_at(&self, elapsed: f64) -> Option<&Frame> {
+        let length = self.length();
+
+        let mut t = match self.mode {
+            PlayMode::Loop if length > 0.0 => elapsed.rem_euclid(length),
+            _ => elapsed,
+        };
+
+        for frame in &self.frames {
+            if t < frame.duration {
+                return Some(frame);
+            }
+            t -= frame.duration;
+        }
+
+        self.frames.last()
+    }
+}
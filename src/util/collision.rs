@@ -1,8 +1,16 @@
 use rapier2d::prelude::InteractionGroups;
 
 static GLOBAL: InteractionGroups = InteractionGroups::all();
-static SHIPS: InteractionGroups = InteractionGroups::new(0b01, 0b11);
-static BULLETS: InteractionGroups = InteractionGroups::new(0b10, 0b11);
+static SHIPS: InteractionGroups = InteractionGroups::new(0b0001, 0b111111);
+static BULLETS: InteractionGroups = InteractionGroups::new(0b0010, 0b0011);
+/// Only used for ray casts, beams pass through projectiles.
+static BEAMS: InteractionGroups = InteractionGroups::new(0b0100, 0b0001);
+/// Pieces of destroyed ships, they only bump into ships and each other.
+static DEBRIS: InteractionGroups = InteractionGroups::new(0b1000, 0b1001);
+/// Sensors floating around for ships to collect.
+static PICKUPS: InteractionGroups = InteractionGroups::new(0b10000, 0b0001);
+/// Docking zones around stations, only ships trigger them.
+static DOCKS: InteractionGroups = InteractionGroups::new(0b100000, 0b0001);
 
 pub struct Collision;
 
@@ -11,7 +19,15 @@ impl Collision {
         return match s.to_lowercase().as_str() {
             "ships" => &SHIPS,
             "bullets" => &BULLETS,
+            "beams" => &BEAMS,
+            "debris" => &DEBRIS,
+            "pickups" => &PICKUPS,
+            "docks" => &DOCKS,
             _ => &GLOBAL,
         };
     }
+
+    pub fn is(groups: &InteractionGroups, s: &str) -> bool {
+        groups == Self::from_str(s)
+    }
 }
@@ -1,10 +1,9 @@
-use macroquad::models::{draw_mesh, Mesh, Vertex};
-use macroquad::prelude::{self, vec2, vec3, Color, Vec2, Vec3, Vec3Swizzles, RED};
+use macroquad::models::{self, Mesh, Vertex};
+use macroquad::prelude::{self, vec2, vec3, Color, Vec2, Vec3, Vec3Swizzles, WHITE};
 
-use nalgebra::{point, vector};
+use nalgebra::{point, vector, Point2};
 use once_cell::sync::Lazy;
 use rapier2d::math::{Isometry, Real};
-use rapier2d::prelude::{Collider, ColliderSet, RigidBody};
 
 use crate::util::screen::world_size;
 
@@ -29,56 +28,95 @@ fn offsets() -> Vec<Vec2> {
 
 pub static OFFSETS: Lazy<Vec<Vec2>> = Lazy::new(offsets);
 
-fn draw_collider(collider: Option<&Collider>) -> Option<()> {
-    let polygon = collider?.shape().as_compound()?;
+/// Per-draw color changes, applied to the vertex colors of a mesh.
+#[derive(Debug, Clone, Copy)]
+pub struct Modulation {
+    /// Multiplied with every vertex color.
+    pub tint: Color,
+    /// Added on top of the tinted color, scaled by its own alpha.
+    pub flash: Color,
+    pub alpha: f32,
+}
 
-    let transform = collider?.position();
+impl Default for Modulation {
+    fn default() -> Self {
+        Self {
+            tint: WHITE,
+            flash: Color::new(0.0, 0.0, 0.0, 0.0),
+            alpha: 1.0,
+        }
+    }
+}
 
-    let points = polygon
-        .shapes()
-        .iter()
-        .filter_map(|v| Some(v.1.as_convex_polygon()?.points().to_vec()))
-        .flat_map(|v| {
-            v.iter()
-                .map(|v| transform.transform_point(v))
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
+impl Modulation {
+    pub fn apply(&self, color: Color) -> Color {
+        let Modulation { tint, flash, alpha } = self;
+
+        Color::new(
+            (color.r * tint.r + flash.r * flash.a).min(1.0),
+            (color.g * tint.g + flash.g * flash.a).min(1.0),
+            (color.b * tint.b + flash.b * flash.a).min(1.0),
+            color.a * tint.a * alpha,
+        )
+    }
+}
+
+pub fn draw(resource: &Resource, transform: Isometry<Real>, modulation: &Modulation) {
+    draw_mesh(resource, &resource.mesh, transform, modulation);
+}
 
-    let length = points.len();
+/// Draws `mesh` as if it was the mesh of `resource`, i.e. centered the same way.
+pub fn draw_mesh(
+    resource: &Resource,
+    mesh: &Mesh,
+    transform: Isometry<Real>,
+    modulation: &Modulation,
+) {
+    let offset = vector!(resource.width / TWO, resource.height / TWO);
 
-    points.iter().enumerate().for_each(|(idx, p1)| {
-        let p2 = points[(idx + 1) % length];
-        draw_line(p1.x, p1.y, p2.x, p2.y, 1.5, RED);
+    draw_transformed(mesh, modulation, |xy| {
+        transform.transform_point(&(xy - offset))
     });
-
-    Some(())
 }
 
-pub fn draw_colliders(rigid_body: &RigidBody, collider_set: &ColliderSet) {
-    rigid_body.colliders().iter().for_each(|c| {
-        draw_collider(collider_set.get(*c));
-    })
+/// Draws a mesh that is already centered on its origin, e.g. a fragment.
+pub fn draw_centered(mesh: &Mesh, transform: Isometry<Real>, modulation: &Modulation) {
+    draw_transformed(mesh, modulation, |xy| transform.transform_point(&xy));
 }
 
-pub fn draw(resource: &Resource, transform: Isometry<Real>) {
-    let offset = vector!(resource.width / TWO, resource.height / TWO);
-
-    let mesh = &resource.mesh;
+/// Draws the mesh of `resource` stretched to `length` along its x axis,
+/// starting at its left edge, e.g. for beams.
+pub fn draw_stretched(
+    resource: &Resource,
+    transform: Isometry<Real>,
+    length: Real,
+    modulation: &Modulation,
+) {
+    let scale = length / resource.width;
+    let offset = resource.height / TWO;
+
+    draw_transformed(&resource.mesh, modulation, |xy| {
+        transform.transform_point(&point!(xy.x * scale, xy.y - offset))
+    });
+}
 
+/// Draws `mesh` with every vertex moved by `f`, once per world copy.
+fn draw_transformed(
+    mesh: &Mesh,
+    modulation: &Modulation,
+    f: impl Fn(Point2<Real>) -> Point2<Real>,
+) {
     let vertices = mesh
         .vertices
         .iter()
         .map(|v| -> Vertex {
             let xy = v.position.xy();
-            let xy = point!(xy.x, xy.y);
-            let xy = xy - offset;
-            let xy = transform.transform_point(&xy);
+            let xy = f(point!(xy.x, xy.y));
 
             Vertex {
                 position: Vec3::new(xy[0], xy[1], v.position.z),
                 uv: v.uv,
-                color: v.color,
+                color: modulation.apply(v.color),
             }
         })
         .collect::<Vec<_>>();
@@ -100,7 +138,7 @@ pub fn draw(resource: &Resource, transform: Isometry<Real>) {
             })
             .collect::<Vec<_>>();
 
-        draw_mesh(&Mesh {
+        models::draw_mesh(&Mesh {
             vertices: new_vert,
             indices,
             texture,
@@ -117,3 +155,19 @@ pub fn draw_line(x1: f32, y1: f32, x2: f32, y2: f32, thickness: f32, color: Colo
 
     Some(())
 }
+
+pub fn draw_circle_lines(x: f32, y: f32, r: f32, thickness: f32, color: Color) {
+    OFFSETS.iter().for_each(|offset| {
+        let (o_x, o_y) = (*offset).into();
+
+        prelude::draw_circle_lines(x + o_x, y + o_y, r, thickness, color);
+    });
+}
+
+pub fn draw_text(text: &str, x: f32, y: f32, font_size: f32, color: Color) {
+    OFFSETS.iter().for_each(|offset| {
+        let (o_x, o_y) = (*offset).into();
+
+        prelude::draw_text(text, x + o_x, y + o_y, font_size, color);
+    });
+}
@@ -0,0 +1,80 @@
+use json::JsonValue;
+use macroquad::prelude::Color;
+
+use super::loader::load_list;
+
+/// A side ships belong to, loaded from the faction catalogue. How the player
+/// stands with it is tracked by `Reputation`.
+#[derive(Clone, Debug)]
+pub struct Faction {
+    pub id: String,
+    pub name: String,
+    pub color: Color,
+    /// Where the player starts out with it.
+    pub reputation: f32,
+    /// Relative chance a spawned ship belongs to it.
+    pub spawn_weight: u32,
+    /// Ids of the factions that like seeing its ships destroyed.
+    pub rivals: Vec<String>,
+}
+
+impl Faction {
+    fn from_json(value: &JsonValue) -> Option<Faction> {
+        let id = value["id"].as_str()?.to_string();
+        let name = value["name"].as_str().unwrap_or(&id).to_string();
+
+        let channel = |i: usize| value["color"][i].as_f32().unwrap_or(1.0);
+        let color = Color::new(channel(0), channel(1), channel(2), 1.0);
+
+        let rivals = value["rivals"]
+            .members()
+            .filter_map(|v| Some(v.as_str()?.to_string()))
+            .collect();
+
+        Some(Faction {
+            id,
+            name,
+            color,
+            reputation: value["reputation"].as_f32().unwrap_or(0.0),
+            spawn_weight: value["spawn_weight"].as_u32().unwrap_or(0),
+            rivals,
+        })
+    }
+}
+
+pub struct Factions {
+    pub factions: Vec<Faction>,
+}
+
+impl Factions {
+    pub fn get(&self, id: &str) -> Option<&Faction> {
+        self.factions.iter().find(|v| v.id == id)
+    }
+
+    /// A random faction, by spawn weight.
+    pub fn roll(&self, roll: u32) -> Option<&Faction> {
+        let total: u32 = self.factions.iter().map(|v| v.spawn_weight).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = roll % total;
+
+        self.factions.iter().find(|v| {
+            if roll < v.spawn_weight {
+                return true;
+            }
+
+            roll -= v.spawn_weight;
+            false
+        })
+    }
+}
+
+pub async fn load_factions(path: &str) -> Factions {
+    log::info!("loading factions at {}", path);
+
+    let factions = load_list(path, "faction", Faction::from_json).await;
+
+    Factions { factions }
+}
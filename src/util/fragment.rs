@@ -0,0 +1,119 @@
+use macroquad::{
+    models::{Mesh, Vertex},
+    prelude::vec3,
+};
+use nalgebra::point;
+use rapier2d::{
+    math::{Point, Real, Vector},
+    prelude::Collider,
+};
+
+use super::screen::TWO;
+
+/// A piece of a resource a destroyed ship breaks into: one convex part of its
+/// compound collider, and the triangles of its mesh that lie on that part.
+pub struct Fragment {
+    /// Relative to `offset`.
+    pub mesh: Mesh,
+    /// Outline of the collider part, relative to `offset`.
+    pub points: Vec<Point<Real>>,
+    /// Center of the piece, relative to the center of the resource.
+    pub offset: Vector<Real>,
+}
+
+fn centroid(points: &[Point<Real>]) -> Point<Real> {
+    let sum = points.iter().fold(Vector::zeros(), |acc, v| acc + v.coords);
+
+    Point::from(sum / points.len() as Real)
+}
+
+fn contains(polygon: &[Point<Real>], p: Point<Real>) -> bool {
+    let length = polygon.len();
+    let side = |i: usize| {
+        let (a, b) = (polygon[i], polygon[(i + 1) % length]);
+        (b - a).perp(&(p - a))
+    };
+
+    let sides = (0..length).map(side).collect::<Vec<_>>();
+
+    sides.iter().all(|v| *v >= 0.0) || sides.iter().all(|v| *v <= 0.0)
+}
+
+/// Splits `mesh` along the convex parts of `collider`. Both are in the
+/// coordinates of the SVG, which is `width` by `height`.
+pub fn fragments(collider: &Collider, mesh: &Mesh, width: Real, height: Real) -> Vec<Fragment> {
+    let center = Vector::new(width / TWO, height / TWO);
+
+    let parts = match collider.shape().as_compound() {
+        Some(v) => v
+            .shapes()
+            .iter()
+            .filter_map(|(local, shape)| {
+                let points = shape.as_convex_polygon()?.points();
+                Some(points.iter().map(|v| local * v).collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>(),
+        None => return vec![],
+    };
+    let centroids = parts.iter().map(|v| centroid(v)).collect::<Vec<_>>();
+
+    let mut triangles: Vec<Vec<[Vertex; 3]>> = parts.iter().map(|_| vec![]).collect();
+
+    mesh.indices.chunks_exact(3).for_each(|indices| {
+        let vertices = [
+            mesh.vertices[indices[0] as usize],
+            mesh.vertices[indices[1] as usize],
+            mesh.vertices[indices[2] as usize],
+        ];
+        let points = vertices
+            .iter()
+            .map(|v| point!(v.position.x, v.position.y))
+            .collect::<Vec<_>>();
+        let c = centroid(&points);
+
+        // triangles outside of the collider go to the closest part
+        let part = parts
+            .iter()
+            .position(|v| contains(v, c))
+            .unwrap_or_else(|| {
+                (0..centroids.len())
+                    .min_by(|a, b| {
+                        let a = (centroids[*a] - c).norm();
+                        let b = (centroids[*b] - c).norm();
+                        a.total_cmp(&b)
+                    })
+                    .unwrap_or(0)
+            });
+
+        if let Some(v) = triangles.get_mut(part) {
+            v.push(vertices);
+        }
+    });
+
+    parts
+        .iter()
+        .zip(centroids)
+        .zip(triangles)
+        .map(|((points, c), triangles)| {
+            let vertices = triangles
+                .iter()
+                .flatten()
+                .map(|v| Vertex {
+                    position: vec3(v.position.x - c.x, v.position.y - c.y, v.position.z),
+                    ..*v
+                })
+                .collect::<Vec<_>>();
+            let indices = (0..vertices.len() as u16).collect();
+
+            Fragment {
+                mesh: Mesh {
+                    vertices,
+                    indices,
+                    texture: None,
+                },
+                points: points.iter().map(|v| Point::from(v - c)).collect(),
+                offset: c.coords - center,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,45 @@
+use json::JsonValue;
+
+/// Every entry of the JSON array in `source` that `parse` accepts, the
+/// others are skipped with a warning. `None` if it isn't valid JSON.
+pub fn parse_list<T>(
+    source: &str,
+    kind: &str,
+    parse: impl Fn(&JsonValue) -> Option<T>,
+) -> Option<Vec<T>> {
+    let list = json::parse(source)
+        .ok()?
+        .members()
+        .filter_map(|v| {
+            let result = parse(v);
+            if result.is_none() {
+                log::warn!("skipping invalid {} {}", kind, v.dump());
+            }
+            result
+        })
+        .collect();
+
+    Some(list)
+}
+
+/// `parse_list` of the file at `path`, which has to exist and be valid JSON.
+pub async fn load_list<T>(
+    path: &str,
+    kind: &str,
+    parse: impl Fn(&JsonValue) -> Option<T>,
+) -> Vec<T> {
+    let file = load_text(path).await;
+
+    parse_list(&file, kind, parse).unwrap()
+}
+
+/// The contents of the file at `path`, which has to exist.
+pub async fn load_text(path: &str) -> String {
+    // tests run without a window, which macroquad needs to load files
+    #[cfg(test)]
+    let text = std::fs::read_to_string(path);
+    #[cfg(not(test))]
+    let text = macroquad::file::load_string(path).await;
+
+    text.unwrap()
+}
@@ -1,10 +1,16 @@
+pub mod animation;
 pub mod bg;
 pub mod collision;
 pub mod draw;
+pub mod faction;
+pub mod fragment;
+pub mod loader;
 pub mod math;
+pub mod module;
 pub mod resource;
 
 #[macro_use]
 pub mod resource_util;
 
 pub mod screen;
+pub mod station;
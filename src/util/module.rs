@@ -0,0 +1,158 @@
+use json::JsonValue;
+use rapier2d::math::Real;
+
+use super::loader::load_list;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlotKind {
+    Weapon,
+    Engine,
+    Reactor,
+    Shield,
+    Cargo,
+    Utility,
+}
+
+impl SlotKind {
+    pub fn from_str(s: &str) -> Option<SlotKind> {
+        match s.to_lowercase().as_str() {
+            "weapon" => Some(SlotKind::Weapon),
+            "engine" => Some(SlotKind::Engine),
+            "reactor" => Some(SlotKind::Reactor),
+            "shield" => Some(SlotKind::Shield),
+            "cargo" => Some(SlotKind::Cargo),
+            "utility" => Some(SlotKind::Utility),
+            _ => None,
+        }
+    }
+}
+
+/// A place on a ship a module fits in, as declared in the resource attributes.
+#[derive(Clone, Copy, Debug)]
+pub struct Slot {
+    pub kind: SlotKind,
+    /// Weapon hardpoints only, index into the fire points of the resource.
+    pub fire_point: Option<usize>,
+}
+
+impl Slot {
+    pub fn from_json(value: &JsonValue) -> Option<Slot> {
+        let kind = SlotKind::from_str(value["kind"].as_str()?)?;
+        let fire_point = value["fire_point"].as_usize();
+
+        if kind == SlotKind::Weapon && fire_point.is_none() {
+            log::warn!("weapon hardpoint without a fire point");
+            return None;
+        }
+
+        Some(Slot { kind, fire_point })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ModuleStats {
+    /// Name of a weapon preset.
+    Weapon(String),
+    Engine {
+        thrust: Real,
+        reverse_thrust: Real,
+        strafe_thrust: Real,
+        max_speed: Real,
+        afterburner: Real,
+        afterburner_max_speed: Real,
+    },
+    Reactor {
+        energy: f32,
+        recharge: f32,
+    },
+    Shield {
+        strength: f32,
+        regen: f32,
+        regen_delay: f64,
+    },
+    Cargo {
+        capacity: u32,
+    },
+    Utility {
+        armor: f32,
+        hull: f32,
+        ram_plating: bool,
+    },
+}
+
+/// Something that can be installed into a slot, loaded from the module
+/// catalogue.
+#[derive(Clone, Debug)]
+pub struct Module {
+    pub id: String,
+    pub name: String,
+    pub kind: SlotKind,
+    pub stats: ModuleStats,
+    /// In credits, at a shipyard.
+    pub price: u32,
+}
+
+impl Module {
+    fn from_json(value: &JsonValue) -> Option<Module> {
+        let id = value["id"].as_str()?.to_string();
+        let name = value["name"].as_str().unwrap_or(&id).to_string();
+        let kind = SlotKind::from_str(value["slot"].as_str()?)?;
+
+        let f32_or = |key: &str, default: f32| value[key].as_f32().unwrap_or(default);
+
+        let stats = match kind {
+            SlotKind::Weapon => ModuleStats::Weapon(value["weapon"].as_str()?.to_string()),
+            SlotKind::Engine => ModuleStats::Engine {
+                thrust: f32_or("thrust", 0.0),
+                reverse_thrust: f32_or("reverse_thrust", 0.0),
+                strafe_thrust: f32_or("strafe_thrust", 0.0),
+                max_speed: f32_or("max_speed", 0.0),
+                afterburner: f32_or("afterburner", 1.0),
+                afterburner_max_speed: f32_or("afterburner_max_speed", 0.0),
+            },
+            SlotKind::Reactor => ModuleStats::Reactor {
+                energy: f32_or("energy", 0.0),
+                recharge: f32_or("recharge", 0.0),
+            },
+            SlotKind::Shield => ModuleStats::Shield {
+                strength: f32_or("strength", 0.0),
+                regen: f32_or("regen", 0.0),
+                regen_delay: value["regen_delay"].as_f64().unwrap_or(0.0),
+            },
+            SlotKind::Cargo => ModuleStats::Cargo {
+                capacity: value["capacity"].as_u32().unwrap_or(0),
+            },
+            SlotKind::Utility => ModuleStats::Utility {
+                armor: f32_or("armor", 0.0),
+                hull: f32_or("hull", 0.0),
+                ram_plating: value["ram_plating"].as_bool().unwrap_or(false),
+            },
+        };
+
+        Some(Module {
+            id,
+            name,
+            kind,
+            stats,
+            price: value["price"].as_u32().unwrap_or(0),
+        })
+    }
+}
+
+pub struct Catalogue {
+    pub modules: Vec<Module>,
+}
+
+impl Catalogue {
+    pub fn get(&self, id: &str) -> Option<&Module> {
+        self.modules.iter().find(|v| v.id == id)
+    }
+}
+
+pub async fn load_catalogue(path: &str) -> Catalogue {
+    log::info!("loading module catalogue at {}", path);
+
+    let modules = load_list(path, "module", Module::from_json).await;
+
+    Catalogue { modules }
+}
@@ -7,7 +7,7 @@ use lyon::{
 };
 use macroquad::{file, models::Mesh, prelude::vec2};
 
-use nalgebra::{point, Point2};
+use nalgebra::{point, vector, Point2};
 use once_cell::{sync::Lazy, sync::OnceCell};
 use rapier2d::{
     math::{Isometry, Real},
@@ -20,10 +20,13 @@ use rapier2d::{
 use roxmltree::Document;
 use usvg::{Node, NodeExt, NodeKind, Options, Tree};
 
-use crate::util::screen::TWO;
+use crate::{render::layer::Layer, util::screen::TWO};
 
 use super::{
+    animation::{Clip, ClipSpec, Frame},
     collision::Collision,
+    fragment::{fragments, Fragment},
+    module::Slot,
     resource_util::{fill, paths, stroke, Geometry},
 };
 
@@ -52,10 +55,47 @@ impl FirePoints {
     }
 }
 
+/// What moves the rigid body, ships are dynamic by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BodyKind {
+    Dynamic,
+    /// Never moves, e.g. stations.
+    Fixed,
+    /// Moved by setting its position, nothing pushes it around.
+    Kinematic,
+}
+
+impl BodyKind {
+    fn from_str(s: &str) -> Option<BodyKind> {
+        match s.to_lowercase().as_str() {
+            "dynamic" => Some(BodyKind::Dynamic),
+            "fixed" => Some(BodyKind::Fixed),
+            "kinematic" => Some(BodyKind::Kinematic),
+            _ => None,
+        }
+    }
+
+    fn builder(self) -> RigidBodyBuilder {
+        match self {
+            BodyKind::Dynamic => RigidBodyBuilder::new_dynamic(),
+            BodyKind::Fixed => RigidBodyBuilder::new_static(),
+            BodyKind::Kinematic => RigidBodyBuilder::new_kinematic_position_based(),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Attributes {
     collision_group: Option<InteractionGroups>,
     fire_points: Option<FirePoints>,
+    layer: Option<Layer>,
+    animations: Vec<(String, ClipSpec)>,
+    /// Where modules can be installed, in order.
+    slots: Vec<Slot>,
+    ram_resistance: Option<f32>,
+    /// Reports overlaps instead of colliding.
+    sensor: bool,
+    body: Option<BodyKind>,
 }
 
 #[derive(Clone)]
@@ -63,6 +103,9 @@ pub struct Info {
     pub attributes: Option<Attributes>,
 
     pub collider: Option<Collider>,
+    /// Sensor from the element with the id "dock", ships that hold still in
+    /// it dock.
+    pub dock: Option<Collider>,
     pub rigid_body: Option<RigidBody>,
 }
 
@@ -74,6 +117,24 @@ impl Info {
     pub fn fire_points(&self) -> Option<&FirePoints> {
         self.attributes.as_ref()?.fire_points.as_ref()
     }
+
+    pub fn layer(&self) -> Option<Layer> {
+        self.attributes.as_ref()?.layer
+    }
+
+    pub fn ram_resistance(&self) -> Option<f32> {
+        self.attributes.as_ref()?.ram_resistance
+    }
+
+    pub fn slots(&self) -> &[Slot] {
+        self.attributes.as_ref().map_or(&[], |v| v.slots.as_slice())
+    }
+
+    fn animations(&self) -> &[(String, ClipSpec)] {
+        self.attributes
+            .as_ref()
+            .map_or(&[], |v| v.animations.as_slice())
+    }
 }
 
 pub struct Resource {
@@ -82,16 +143,24 @@ pub struct Resource {
     pub height: Real,
 
     pub info: Info,
+
+    /// Clips by name, their frames are drawn over `mesh`.
+    pub animations: HashMap<String, Clip>,
+
+    /// What it breaks into when destroyed.
+    pub fragments: Vec<Fragment>,
 }
 
 static OPT: Lazy<Options> = Lazy::new(svg_option);
 static HIDDEN_ELEMENTS: Lazy<HashSet<String>> =
-    Lazy::new(|| HashSet::from(["collider".to_string()]));
+    Lazy::new(|| HashSet::from(["collider".to_string(), "dock".to_string()]));
 
 fn svg_option() -> Options {
-    let opt = Options::default();
-
-    return opt;
+    // animation frames are usually groups, which need to survive parsing
+    Options {
+        keep_named_groups: true,
+        ..Options::default()
+    }
 }
 
 fn tessellation_build(
@@ -135,6 +204,33 @@ fn tessellation(nodes: &HashMap<String, Node>) -> Geometry {
     return mesh;
 }
 
+/// Tessellates `node` and everything below it.
+fn tessellation_tree(node: &Node) -> Geometry {
+    let mut fill_tess = FillTessellator::new();
+    let mut stroke_tess = StrokeTessellator::new();
+    let mut mesh: Geometry = VertexBuffers::new();
+
+    for node in node.descendants() {
+        if HIDDEN_ELEMENTS.contains(&node.id().to_string()) {
+            continue;
+        }
+
+        if let NodeKind::Path(ref p) = *node.borrow() {
+            tessellation_build(p, &mut mesh, &mut fill_tess, &mut stroke_tess)
+        }
+    }
+
+    return mesh;
+}
+
+fn to_mesh(geometry: Geometry) -> Mesh {
+    Mesh {
+        vertices: geometry.vertices,
+        indices: geometry.indices,
+        texture: None,
+    }
+}
+
 fn get_attributes(elements: &HashMap<String, roxmltree::Node>) -> Option<Attributes> {
     let attributes = elements
         .get("attributes")?
@@ -158,17 +254,45 @@ fn get_attributes(elements: &HashMap<String, roxmltree::Node>) -> Option<Attribu
         None
     };
 
+    let layer = attributes["layer"].as_str().and_then(Layer::from_str);
+
+    let animations = attributes["animations"]
+        .entries()
+        .filter_map(|(name, clip)| Some((name.to_string(), ClipSpec::from_json(clip)?)))
+        .collect();
+
+    // the slots after a bad one move up, loadouts warn about what no longer fits
+    let slots = attributes["slots"]
+        .members()
+        .filter_map(|v| {
+            let slot = Slot::from_json(v);
+            if slot.is_none() {
+                log::warn!("skipping invalid slot {}", v.dump());
+            }
+
+            slot
+        })
+        .collect();
+
+    let ram_resistance = attributes["ram_resistance"].as_f32();
+    let sensor = attributes["sensor"].as_bool().unwrap_or(false);
+    let body = attributes["body"].as_str().and_then(BodyKind::from_str);
+
     Some(Attributes {
         collision_group,
         fire_points,
+        layer,
+        animations,
+        slots,
+        ram_resistance,
+        sensor,
+        body,
     })
 }
 
-fn get_collider(
-    nodes: &HashMap<String, Node>,
-    attributes: Option<&Attributes>,
-) -> Option<Collider> {
-    let path = tessellation_single(nodes.get("collider")?);
+/// One convex shape per triangle of the tessellated `node`.
+fn compound_shapes(node: &Node) -> Vec<(Isometry<Real>, SharedShape)> {
+    let path = tessellation_single(node);
 
     let vertices = path
         .vertices
@@ -187,7 +311,7 @@ fn get_collider(
         .map(|v| vertices[v as usize])
         .collect::<Vec<_>>();
 
-    let shapes = vertices
+    vertices
         .chunks_exact(3)
         .map(|v| {
             (
@@ -195,7 +319,14 @@ fn get_collider(
                 SharedShape::convex_hull(&[v[0], v[1], v[2]]).unwrap(),
             )
         })
-        .collect();
+        .collect()
+}
+
+fn get_collider(
+    nodes: &HashMap<String, Node>,
+    attributes: Option<&Attributes>,
+) -> Option<Collider> {
+    let shapes = compound_shapes(nodes.get("collider")?);
 
     let mut result = if let Some(collision_group) = attributes?.collision_group {
         ColliderBuilder::compound(shapes)
@@ -211,10 +342,79 @@ fn get_collider(
     result.set_position(offset.into());
 
     result.set_active_events(ActiveEvents::all());
+    result.set_sensor(attributes.is_some_and(|v| v.sensor));
 
     Some(result)
 }
 
+/// Unlike the collider, the dock may reach past the edges of the file, so
+/// it's centered on the file instead of on itself.
+fn get_dock(nodes: &HashMap<String, Node>, width: Real, height: Real) -> Option<Collider> {
+    let mut result = ColliderBuilder::compound(compound_shapes(nodes.get("dock")?))
+        .collision_groups(*Collision::from_str("docks"))
+        .solver_groups(*Collision::from_str("docks"))
+        .sensor(true)
+        .active_events(ActiveEvents::INTERSECTION_EVENTS)
+        .build();
+
+    result.set_position(vector![-width / TWO, -height / TWO].into());
+
+    Some(result)
+}
+
+/// Ids of every element that belongs to an animation frame in this file, so
+/// they can be left out of the base mesh.
+fn frame_elements(info: &Info, nodes: &HashMap<String, Node>) -> HashSet<String> {
+    info.animations()
+        .iter()
+        .flat_map(|(_, clip)| clip.frames.iter())
+        .filter(|v| !ClipSpec::is_file(v))
+        .filter_map(|v| nodes.get(v))
+        .flat_map(|v| v.descendants().map(|v| v.id().to_string()))
+        .collect()
+}
+
+async fn load_frame_file(path: &str) -> Mesh {
+    log::info!("loading animation frame at {}", path);
+
+    let file = file::load_string(path).await.unwrap();
+    let tree = Tree::from_str(&file, &OPT.to_ref()).unwrap();
+
+    to_mesh(tessellation_tree(&tree.root()))
+}
+
+async fn load_clips(info: &Info, nodes: &HashMap<String, Node>) -> HashMap<String, Clip> {
+    let mut clips = HashMap::new();
+
+    for (name, spec) in info.animations() {
+        let mut frames = vec![];
+
+        for (i, frame) in spec.frames.iter().enumerate() {
+            let mesh = if ClipSpec::is_file(frame) {
+                load_frame_file(frame).await
+            } else if let Some(node) = nodes.get(frame) {
+                to_mesh(tessellation_tree(node))
+            } else {
+                log::warn!("animation {} refers to missing frame {}", name, frame);
+                continue;
+            };
+
+            let duration = spec.durations.get(i).copied().unwrap_or(0.1);
+
+            frames.push(Frame { mesh, duration });
+        }
+
+        let clip = Clip {
+            frames,
+            mode: spec.mode,
+        };
+
+        clips.insert(name.clone(), clip);
+    }
+
+    clips
+}
+
 pub async fn load_resource(path: &str) -> Resource {
     log::info!("loading resource at {}", path);
 
@@ -236,37 +436,49 @@ pub async fn load_resource(path: &str) -> Resource {
         })
         .collect::<HashMap<_, _>>();
 
-    let drawn = nodes
-        .clone()
-        .into_iter()
-        .filter(|v| HIDDEN_ELEMENTS.contains(&v.0) == false)
-        .collect();
-
-    let mesh = tessellation(&drawn);
-    let mesh = Mesh {
-        vertices: mesh.vertices,
-        indices: mesh.indices,
-        texture: None,
-    };
-
     let attributes = get_attributes(&elements);
     let collider = get_collider(&nodes, attributes.as_ref());
 
-    let rigid_body = Some(RigidBodyBuilder::new_dynamic().build());
+    let (width, height) = tree.svg_node().size.to_screen_size().dimensions();
+    let (width, height) = (width as f32, height as f32);
+
+    let dock = get_dock(&nodes, width, height);
+
+    let body = attributes.as_ref().and_then(|v| v.body);
+    let rigid_body = Some(body.unwrap_or(BodyKind::Dynamic).builder().build());
 
     let info = Info {
         attributes,
         collider,
+        dock,
         rigid_body,
     };
 
-    let (width, height) = tree.svg_node().size.to_screen_size().dimensions();
-    let (width, height) = (width as f32, height as f32);
+    let frame_elements = frame_elements(&info, &nodes);
+
+    let drawn = nodes
+        .clone()
+        .into_iter()
+        .filter(|v| HIDDEN_ELEMENTS.contains(&v.0) == false)
+        .filter(|v| !frame_elements.contains(&v.0))
+        .collect();
+
+    let mesh = to_mesh(tessellation(&drawn));
+
+    let animations = load_clips(&info, &nodes).await;
+
+    let fragments = info
+        .collider
+        .as_ref()
+        .map(|v| fragments(v, &mesh, width, height))
+        .unwrap_or_default();
 
     Resource {
         mesh,
         width,
         height,
         info,
+        animations,
+        fragments,
     }
 }
@@ -1,22 +1,47 @@
 use macroquad::{
-    camera::Camera2D,
-    prelude::{mouse_position, vec2, Vec2},
+    camera::{Camera, Camera2D},
+    prelude::{mouse_position, vec2, vec3, Rect, Vec2},
     window::*,
 };
-use nalgebra::point;
-use rapier2d::math::{Point, Real};
+use nalgebra::{point, vector};
+use rapier2d::math::{Point, Real, Vector};
 
 pub const TWO: f32 = 2.0;
 pub const THREE: f32 = 3.0;
 
-pub fn screen_center() -> (f32, f32) {
-    (screen_width() / TWO, screen_height() / TWO)
-}
+/// Logical size of the view, independent of the window it ends up in.
+pub const VIEW_WIDTH: f32 = 1920.0;
+pub const VIEW_HEIGHT: f32 = 1080.0;
+
+/// A sector is this many views wide and tall.
+pub const SECTOR_VIEWS: f32 = THREE;
 
 pub fn screen_size() -> (f32, f32) {
     (screen_width(), screen_height())
 }
 
+/// Toggles fullscreen at runtime. Note that miniquad ignores this on Linux,
+/// use `--fullscreen` there instead.
+pub fn set_fullscreen(fullscreen: bool) {
+    unsafe { get_internal_gl() }
+        .quad_context
+        .set_fullscreen(fullscreen);
+}
+
+pub fn view_size() -> (f32, f32) {
+    (VIEW_WIDTH, VIEW_HEIGHT)
+}
+
+/// The part of the window the view is drawn into, in pixels. The view keeps
+/// its aspect ratio, the rest of the window is left black.
+pub fn letterbox() -> Rect {
+    let (screen_w, screen_h) = screen_size();
+    let scale = (screen_w / VIEW_WIDTH).min(screen_h / VIEW_HEIGHT);
+    let (w, h) = (VIEW_WIDTH * scale, VIEW_HEIGHT * scale);
+
+    Rect::new((screen_w - w) / TWO, (screen_h - h) / TWO, w, h)
+}
+
 pub fn world_min_coord() -> (f32, f32) {
     let (w, h) = world_size();
     (-w / TWO, -h / TWO)
@@ -28,16 +53,52 @@ pub fn world_max_coord() -> (f32, f32) {
 }
 
 pub fn world_size() -> (f32, f32) {
-    let (w, h) = screen_size();
-    (w * THREE, h * THREE)
+    let (w, h) = view_size();
+    (w * SECTOR_VIEWS, h * SECTOR_VIEWS)
 }
 
 pub fn world_center() -> (f32, f32) {
     (0.0, 0.0)
 }
 
+/// The letterbox as a camera viewport.
+pub fn letterbox_viewport() -> (i32, i32, i32, i32) {
+    let rect = letterbox();
+
+    (rect.x as i32, rect.y as i32, rect.w as i32, rect.h as i32)
+}
+
+/// Like `Camera2D::screen_to_world`, but aware of the letterbox.
+pub fn screen_to_world(camera: &Camera2D, point: Vec2) -> Vec2 {
+    let viewport = letterbox();
+    let point = vec2(
+        (point.x - viewport.x) / viewport.w * TWO - 1.0,
+        1.0 - (point.y - viewport.y) / viewport.h * TWO,
+    );
+
+    let transform = camera
+        .matrix()
+        .inverse()
+        .transform_point3(vec3(point.x, point.y, 0.0));
+
+    vec2(transform.x, transform.y)
+}
+
+/// Like `Camera2D::world_to_screen`, but aware of the letterbox.
+pub fn world_to_screen(camera: &Camera2D, point: Vec2) -> Vec2 {
+    let viewport = letterbox();
+    let transform = camera
+        .matrix()
+        .transform_point3(vec3(point.x, point.y, 0.0));
+
+    vec2(
+        viewport.x + (transform.x + 1.0) / TWO * viewport.w,
+        viewport.y + (1.0 - transform.y) / TWO * viewport.h,
+    )
+}
+
 pub fn get_world_mouse_pos(camera: &Camera2D) -> Vec2 {
-    camera.screen_to_world(mouse_position().into())
+    screen_to_world(camera, mouse_position().into())
 }
 
 pub fn crop_to_world(pos: Point<Real>) -> Point<Real> {
@@ -58,8 +119,39 @@ pub fn crop_to_world(pos: Point<Real>) -> Point<Real> {
     point!(new[0], new[1])
 }
 
+/// Shortest offset from `from` to `to`, taking the wrapping of the world into
+/// account.
+pub fn wrap_delta(from: Point<Real>, to: Point<Real>) -> Vector<Real> {
+    let (w, h) = world_size();
+    let delta = to - from;
+
+    vector!(
+        (delta.x + w / TWO).rem_euclid(w) - w / TWO,
+        (delta.y + h / TWO).rem_euclid(h) - h / TWO
+    )
+}
+
+/// How far `pos` can travel along `direction` before leaving the world.
+pub fn distance_to_edge(pos: Point<Real>, direction: Vector<Real>) -> Real {
+    let (min_x, min_y) = world_min_coord();
+    let (max_x, max_y) = world_max_coord();
+
+    let axis = |p: Real, d: Real, min: Real, max: Real| {
+        if d > 0.0 {
+            (max - p) / d
+        } else if d < 0.0 {
+            (min - p) / d
+        } else {
+            Real::INFINITY
+        }
+    };
+
+    axis(pos.x, direction.x, min_x, max_x).min(axis(pos.y, direction.y, min_y, max_y))
+}
+
 pub fn make_camera() -> Camera2D {
-    let (w, h) = screen_center();
+    let (w, h) = view_size();
+    let (w, h) = (w / TWO, h / TWO);
 
     Camera2D {
         target: world_center().into(),
@@ -0,0 +1,102 @@
+use json::JsonValue;
+use rapier2d::math::Real;
+
+use super::loader::load_list;
+
+/// What a station offers to ships docked at it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Service {
+    Market,
+    Shipyard,
+    Repair,
+    Missions,
+}
+
+impl Service {
+    pub const ALL: [Service; 4] = [
+        Service::Market,
+        Service::Shipyard,
+        Service::Repair,
+        Service::Missions,
+    ];
+
+    fn from_str(s: &str) -> Option<Service> {
+        match s.to_lowercase().as_str() {
+            "market" => Some(Service::Market),
+            "shipyard" => Some(Service::Shipyard),
+            "repair" => Some(Service::Repair),
+            "missions" => Some(Service::Missions),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Service::Market => "MARKET",
+            Service::Shipyard => "SHIPYARD",
+            Service::Repair => "REPAIR",
+            Service::Missions => "MISSIONS",
+        }
+    }
+}
+
+/// A station placed in the sector, loaded from the station catalogue.
+#[derive(Clone, Debug)]
+pub struct StationSpec {
+    pub id: String,
+    pub name: String,
+    /// Id of the faction that runs it, stations without one dock anybody.
+    pub faction: Option<String>,
+    /// Id of its market in the economy.
+    pub market: Option<String>,
+    pub position: [Real; 2],
+    pub services: Vec<Service>,
+}
+
+impl StationSpec {
+    fn from_json(value: &JsonValue) -> Option<StationSpec> {
+        let id = value["id"].as_str()?.to_string();
+        let name = value["name"].as_str().unwrap_or(&id).to_string();
+
+        let position = [
+            value["position"][0].as_f32()?,
+            value["position"][1].as_f32()?,
+        ];
+
+        let services = value["services"]
+            .members()
+            .filter_map(|v| {
+                let service = Service::from_str(v.as_str()?);
+                if service.is_none() {
+                    log::warn!("{} offers unknown service {}", id, v);
+                }
+                service
+            })
+            .collect();
+
+        Some(StationSpec {
+            faction: value["faction"].as_str().map(str::to_string),
+            market: value["market"].as_str().map(str::to_string),
+            id,
+            name,
+            position,
+            services,
+        })
+    }
+
+    pub fn offers(&self, service: Service) -> bool {
+        self.services.contains(&service)
+    }
+}
+
+pub struct Stations {
+    pub stations: Vec<StationSpec>,
+}
+
+pub async fn load_stations(path: &str) -> Stations {
+    log::info!("loading stations at {}", path);
+
+    let stations = load_list(path, "station", StationSpec::from_json).await;
+
+    Stations { stations }
+}
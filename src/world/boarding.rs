@@ -0,0 +1,168 @@
+use macroquad::prelude::WHITE;
+use rapier2d::math::Real;
+
+use crate::{
+    entity::{
+        ai::Ai,
+        boarding::{BoardingChoice, BoardingLike, Escort},
+        cargo::CargoLike,
+        docking::DockingLike,
+        entity::EntityHolder,
+        health::HealthLike,
+        hold::Hold,
+        physics::PhysicsLike,
+        pickup::PickupKind,
+    },
+    util::screen::wrap_delta,
+};
+
+use super::{event::GameEvent, world::World};
+
+/// How close the player has to stay to a crippled ship to board it.
+const BOARDING_RANGE: Real = 150.0;
+/// Faster than this relative to the ship, and the boarding party can't cross.
+const BOARDING_SPEED: Real = 60.0;
+const BOARDING_TIME: f64 = 3.0;
+/// Fraction of its hull a prize gets patched up to.
+const PRIZE_REPAIR: f32 = 0.5;
+
+impl World {
+    /// The closest crippled ship the player is holding position next to.
+    fn boarding_target(&self, player: EntityHolder) -> Option<EntityHolder> {
+        let rigid_body_set = &*self.rigid_body_set.borrow();
+        let player_entity = self.get_entity(&player)?;
+        let pos = player_entity.pos(rigid_body_set)?;
+        let velocity = player_entity.velocity(rigid_body_set)?;
+
+        self.entities
+            .iter()
+            .filter(|(_, v)| v.boardable.is_some() && v.is_crippled())
+            .filter_map(|(holder, v)| {
+                let distance = wrap_delta(pos, v.pos(rigid_body_set)?).norm();
+                let speed = (v.velocity(rigid_body_set)? - velocity).norm();
+
+                (distance < BOARDING_RANGE && speed < BOARDING_SPEED).then_some((distance, holder))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|v| v.1)
+    }
+
+    /// Advances the boarding of whatever the player holds position next to,
+    /// and carries out the choice once it's boarded.
+    pub(super) fn update_boarding(
+        &mut self,
+        player: EntityHolder,
+        current_time: f64,
+        to_remove: &mut Vec<EntityHolder>,
+    ) -> Option<()> {
+        if self.get_entity(&player)?.is_docked() {
+            return None;
+        }
+
+        let boarded = self.get_entity(&player)?.player?.boarded;
+
+        if let Some(target) = boarded {
+            // it may have been destroyed while the player was deciding
+            if !self.get_entity(&target).is_some_and(|v| v.is_crippled()) {
+                self.get_entity_mut(&player)?.player.as_mut()?.boarded = None;
+                return None;
+            }
+
+            let choice = self.get_entity(&player)?.boarding_choice()?;
+            self.get_entity_mut(&player)?.player.as_mut()?.boarded = None;
+
+            return self.resolve_boarding(player, target, choice, to_remove);
+        }
+
+        let target = self.boarding_target(player);
+        let state = self.get_entity_mut(&player)?.player.as_mut()?;
+
+        state.boarding = Hold::follow(state.boarding, target, current_time);
+
+        if let Some(boarding) = state.boarding {
+            if boarding.is_done(current_time, BOARDING_TIME) {
+                state.boarding = None;
+                state.boarded = Some(boarding.target);
+            }
+        }
+
+        Some(())
+    }
+
+    fn resolve_boarding(
+        &mut self,
+        player: EntityHolder,
+        target: EntityHolder,
+        choice: BoardingChoice,
+        to_remove: &mut Vec<EntityHolder>,
+    ) -> Option<()> {
+        log::info!("boarded ship: {:?}", choice);
+
+        let target_entity = self.get_entity_mut(&target)?;
+        let boardable = target_entity.boardable.take()?;
+        let faction = target_entity.faction.take();
+
+        match choice {
+            BoardingChoice::Loot => {
+                let mut cargo = target_entity.cargo.take().unwrap_or_default();
+
+                let player_entity = self.entities.get_mut(player)?;
+                let taken = match player_entity.cargo.as_mut() {
+                    Some(hold) => hold.take_all(&mut cargo),
+                    None => vec![],
+                };
+                player_entity.update_cargo_mass(&mut self.rigid_body_set.borrow_mut());
+
+                let health = player_entity.health.as_mut()?;
+                health.hull = (health.hull + boardable.salvage).min(health.max_hull);
+
+                self.events
+                    .extend(taken.into_iter().map(|v| GameEvent::PickedUp {
+                        kind: PickupKind::Cargo(v.commodity),
+                        amount: v.amount,
+                    }));
+
+                // what doesn't fit the player's hold is left floating, the
+                // stripped hulk is abandoned
+                let target_entity = self.entities.get_mut(target)?;
+                target_entity.cargo = Some(cargo);
+                let crates = target_entity.dump_cargo(&self.rigid_body_set.borrow());
+                crates.into_iter().flatten().for_each(|v| {
+                    self.handle_mutator(v);
+                });
+
+                to_remove.push(target);
+            }
+            BoardingChoice::Scuttle => {
+                target_entity.health.as_mut()?.hull = 0.0;
+                to_remove.push(target);
+            }
+            BoardingChoice::Prize => {
+                let health = target_entity.health.as_mut()?;
+                health.hull = health.hull.max(health.max_hull * PRIZE_REPAIR);
+
+                target_entity.ai = Some(Ai::escort());
+                target_entity.ram_plating = None;
+                target_entity.escort = Some(Escort { leader: player });
+
+                if let Some(drawable) = target_entity.drawable.as_mut() {
+                    drawable.tint = WHITE;
+                }
+            }
+        }
+
+        // its faction counts a boarded ship as lost
+        if let Some(faction) = faction {
+            self.player_destroyed(faction);
+        }
+
+        Some(())
+    }
+
+    /// How far along the player is boarding, from 0 to 1.
+    pub fn boarding_progress(&self, current_time: f64) -> Option<f32> {
+        let boarding = self.get_entity(&self.player?)?.player?.boarding?;
+
+        Some(boarding.progress(current_time, BOARDING_TIME))
+    }
+}
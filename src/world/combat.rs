@@ -0,0 +1,581 @@
+use std::collections::HashMap;
+
+use macroquad::prelude::{get_frame_time, get_time};
+use nalgebra::{Complex, Unit};
+use rapier2d::{
+    math::{Point, Real, Vector},
+    prelude::{ColliderHandle, InteractionGroups, Ray},
+};
+
+use crate::{
+    entity::{
+        boarding::BoardingLike,
+        entity::{Entity, EntityHolder},
+        health::{HealthLike, Hit},
+        physics::PhysicsLike,
+        projectile::missile::MissileLike,
+        ram::RamLike,
+        status::{StatusEffect, StatusLike},
+    },
+    util::{
+        collision::Collision,
+        screen::{crop_to_world, distance_to_edge, wrap_delta},
+    },
+};
+
+use super::{event::GameEvent, world::World};
+
+/// How hard the player feels a hit, per point of damage.
+const HIT_STRENGTH_PER_DAMAGE: f32 = 0.05;
+/// Explosions of destroyed ships, relative to the larger side of their resource.
+const EXPLOSION_RADIUS_SCALE: Real = 4.0;
+/// Change in velocity of a body right at the center of an explosion, the same
+/// for light and heavy ones.
+const EXPLOSION_SPEED: Real = 300.0;
+/// Ships caught in an explosion catch fire, burning for this much per second.
+const EXPLOSION_BURN: f32 = 4.0;
+const EXPLOSION_BURN_DURATION: f64 = 3.0;
+/// Smallest step a wrapped ray takes, so it never gets stuck on an edge.
+const MIN_RAY_STEP: Real = 1.0;
+/// Change in velocity a ship takes from a collision without damage.
+const SAFE_COLLISION_SPEED: Real = 120.0;
+const COLLISION_DAMAGE_PER_SPEED: f32 = 0.08;
+
+impl World {
+    /// Damages `target` and queues it for removal if that destroyed it. Hits
+    /// by the player cost reputation, friendly hits provoke it. The peace
+    /// zone and friendly fire do no damage.
+    pub(super) fn apply_damage(
+        &mut self,
+        target: EntityHolder,
+        hit: Hit,
+        to_remove: &mut Vec<EntityHolder>,
+    ) -> Option<()> {
+        if self.is_sheltered(target) || self.is_friendly_fire(hit.source, target) {
+            return None;
+        }
+
+        let by_fleet = hit
+            .source
+            .and_then(|v| self.get_entity(&v))
+            .is_some_and(|v| v.is_friendly());
+
+        let target_entity = self.entities.get_mut(target)?;
+        let destroyed = target_entity.damage(hit, &self.rigid_body_set.borrow())?;
+
+        // whatever the reputation, ships fight back
+        if let Some(ai) = target_entity.ai.as_mut().filter(|_| by_fleet) {
+            ai.provoked = true;
+        }
+        let is_player = target_entity.player.is_some();
+        let faction = target_entity.faction;
+
+        if destroyed {
+            if is_player {
+                // there is no game over yet, patch the ship back up
+                log::info!("player destroyed");
+                target_entity.health.as_mut()?.restore();
+            } else {
+                to_remove.push(target);
+            }
+        }
+
+        if is_player {
+            let strength = (hit.damage * HIT_STRENGTH_PER_DAMAGE).min(1.0);
+            self.events.push(GameEvent::PlayerHit { strength });
+        }
+
+        let by_player = hit.source.is_some() && hit.source == self.player;
+        if let Some(faction) = faction.filter(|_| by_player) {
+            self.player_attacked(faction, hit.damage, destroyed);
+        }
+
+        if by_player {
+            self.alarm_convoy(target);
+        }
+
+        Some(())
+    }
+
+    /// Pushes every moving body around `entity` away from it, less the farther
+    /// away it is, and sets the ships in range on fire.
+    pub(super) fn explode(&mut self, entity: &Entity) -> Option<()> {
+        let own = entity.physics?.rigid_body_handle;
+        let center = entity.pos(&self.rigid_body_set.borrow())?;
+        let radius = entity.resource.width.max(entity.resource.height) * EXPLOSION_RADIUS_SCALE;
+
+        let burning = {
+            let rigid_body_set = &*self.rigid_body_set.borrow();
+
+            self.entities
+                .iter()
+                .filter(|(_, v)| v.health.is_some())
+                .filter(|(_, v)| {
+                    v.pos(rigid_body_set)
+                        .is_some_and(|p| wrap_delta(center, p).norm() < radius)
+                })
+                .map(|(holder, _)| holder)
+                .collect::<Vec<_>>()
+        };
+
+        burning.into_iter().for_each(|victim| {
+            let burn = StatusEffect::burn(EXPLOSION_BURN, EXPLOSION_BURN_DURATION);
+            self.apply_effect(victim, Some(burn));
+        });
+
+        let rigid_body_set = &mut *self.rigid_body_set.borrow_mut();
+        rigid_body_set
+            .iter_mut()
+            .filter(|(handle, rigid_body)| *handle != own && rigid_body.is_dynamic())
+            .for_each(|(_, rigid_body)| {
+                let pos = rigid_body.translation();
+                let delta = wrap_delta(center, Point::new(pos.x, pos.y));
+                let distance = delta.norm();
+
+                if distance >= radius || distance <= Real::EPSILON {
+                    return;
+                }
+
+                let falloff = 1.0 - distance / radius;
+                let impulse = rigid_body.mass() * EXPLOSION_SPEED * falloff;
+                rigid_body.apply_impulse(delta / distance * impulse, true);
+            });
+
+        Some(())
+    }
+
+    /// Where two colliders touch, from the contact manifolds of the narrow phase.
+    pub(super) fn contact_point(
+        &self,
+        a: ColliderHandle,
+        b: ColliderHandle,
+    ) -> Option<Point<Real>> {
+        let narrow_phase = self.narrow_phase.borrow();
+        let pair = narrow_phase.contact_pair(a, b)?;
+
+        pair.manifolds
+            .iter()
+            .flat_map(|v| v.data.solver_contacts.iter())
+            .map(|v| v.point)
+            .next()
+    }
+
+    /// Impulse of a collision, from the contact solver or from the velocities
+    /// before it, whichever is larger. The solver splits a hard hit over
+    /// several frames, the velocities catch it in one.
+    fn collision_impulse(
+        &self,
+        (collider_a, a): (ColliderHandle, &Entity),
+        (collider_b, b): (ColliderHandle, &Entity),
+    ) -> Option<Real> {
+        let rigid_body_set = &*self.rigid_body_set.borrow();
+        let narrow_phase = self.narrow_phase.borrow();
+        let pair = narrow_phase.contact_pair(collider_a, collider_b)?;
+
+        let solved = pair
+            .manifolds
+            .iter()
+            .flat_map(|v| v.points.iter())
+            .map(|v| v.data.impulse)
+            .sum::<Real>();
+
+        let normal = pair.manifolds.first()?.data.normal;
+        let closing = (a.physics?.previous_velocity - b.physics?.previous_velocity)
+            .dot(&normal)
+            .abs();
+
+        let mass_a = a.get_rigid_body(rigid_body_set)?.mass();
+        let mass_b = b.get_rigid_body(rigid_body_set)?.mass();
+        let reduced_mass = mass_a * mass_b / (mass_a + mass_b);
+
+        Some(solved.max(reduced_mass * closing))
+    }
+
+    /// Damages both sides of a collision between ships by how much it changed
+    /// their velocity, so the heavier ship takes less of it.
+    pub(super) fn collision_damage(
+        &mut self,
+        (collider_a, a): (ColliderHandle, EntityHolder),
+        (collider_b, b): (ColliderHandle, EntityHolder),
+        point: Option<Point<Real>>,
+        to_remove: &mut Vec<EntityHolder>,
+    ) -> Option<()> {
+        let entity_a = self.get_entity(&a)?;
+        let entity_b = self.get_entity(&b)?;
+
+        let impulse = self.collision_impulse((collider_a, entity_a), (collider_b, entity_b))?;
+
+        let hits = {
+            let rigid_body_set = &*self.rigid_body_set.borrow();
+
+            [(entity_a, entity_b, a), (entity_b, entity_a, b)]
+                .into_iter()
+                .filter(|(target, _, _)| target.health.is_some())
+                .filter_map(|(target, other, holder)| {
+                    let mass = target.get_rigid_body(rigid_body_set)?.mass();
+                    let speed = (impulse / mass - SAFE_COLLISION_SPEED).max(0.0);
+                    let damage = speed * COLLISION_DAMAGE_PER_SPEED * other.ram_damage()
+                        / target.ram_resistance();
+
+                    let other_holder = if holder == a { b } else { a };
+
+                    (damage > 0.0).then_some((holder, Hit::new(damage, point).by(other_holder)))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        hits.into_iter().for_each(|(target, hit)| {
+            self.apply_damage(target, hit, to_remove);
+        });
+
+        Some(())
+    }
+
+    /// Applies the damage of `projectile` to `target` if it was fired by someone
+    /// else, and queues the projectile for removal.
+    pub(super) fn projectile_hit(
+        &mut self,
+        projectile: EntityHolder,
+        target: EntityHolder,
+        point: Option<Point<Real>>,
+        to_remove: &mut Vec<EntityHolder>,
+    ) -> Option<()> {
+        let projectile_entity = self.get_entity(&projectile)?;
+        let hit = projectile_entity.projectile?;
+
+        if hit.source == target {
+            return None;
+        }
+
+        // pellets of the same volley spawn on top of each other
+        let target_source = self.get_entity(&target)?.projectile.map(|v| v.source);
+        if target_source == Some(hit.source) {
+            return None;
+        }
+
+        // a tunneling projectile may not have solver contacts yet
+        let point = point.or_else(|| projectile_entity.pos(&self.rigid_body_set.borrow()));
+
+        to_remove.push(projectile);
+
+        self.apply_damage(
+            target,
+            Hit::new(hit.damage, point).by(hit.source),
+            to_remove,
+        )?;
+        self.apply_effect(target, hit.effect.map(|v| v.by(hit.source)))
+    }
+
+    /// The player and their fleet don't hurt each other.
+    fn is_friendly_fire(&self, source: Option<EntityHolder>, target: EntityHolder) -> bool {
+        let is_friendly = |holder| self.get_entity(&holder).is_some_and(|v| v.is_friendly());
+
+        source.is_some_and(is_friendly) && is_friendly(target)
+    }
+
+    /// Puts `effect` on `target`, for anything that hits, e.g. projectiles,
+    /// blasts and explosions. Like damage, nothing takes effects around the
+    /// station the player is docked at.
+    pub(super) fn apply_effect(
+        &mut self,
+        target: EntityHolder,
+        effect: Option<StatusEffect>,
+    ) -> Option<()> {
+        let effect = effect?;
+
+        if self.is_sheltered(target) {
+            return None;
+        }
+
+        let target_entity = self.entities.get_mut(target)?;
+
+        // only ships take effects, not debris or other projectiles
+        target_entity.health?;
+
+        target_entity.apply_effect(effect, get_time());
+
+        Some(())
+    }
+
+    /// Hull damage from burning ships, collected during the entity update.
+    /// Every burn is dealt by whoever set it.
+    pub(super) fn apply_burns(
+        &mut self,
+        burns: Vec<(EntityHolder, f32, Option<EntityHolder>)>,
+        to_remove: &mut Vec<EntityHolder>,
+    ) {
+        let dt = get_frame_time();
+
+        burns
+            .into_iter()
+            .for_each(|(target, damage_per_second, source)| {
+                let hit = Hit {
+                    source,
+                    ..Hit::continuous(damage_per_second, dt, None).ignoring_shields()
+                };
+                self.apply_damage(target, hit, to_remove);
+            });
+    }
+
+    /// Points every guided weapon at the closest hostile ship in its cone.
+    pub(super) fn update_locks(&mut self, current_time: f64) {
+        let rigid_body_set = &*self.rigid_body_set.borrow();
+
+        let ships = self
+            .entities
+            .iter()
+            .filter(|(_, v)| v.health.is_some())
+            .filter_map(|(holder, v)| Some((holder, v.pos(rigid_body_set)?, v.is_friendly())))
+            .collect::<Vec<_>>();
+
+        for (holder, entity) in self.entities.iter_mut() {
+            if !entity.weapons.iter().any(|v| v.guidance.is_some()) {
+                continue;
+            }
+
+            let transform = match entity.transform(rigid_body_set) {
+                Some(v) => v,
+                None => continue,
+            };
+            let pos = Point::from(transform.translation.vector);
+            let is_player = entity.is_friendly();
+
+            for weapon in entity.weapons.iter_mut() {
+                let guidance = match weapon.guidance {
+                    Some(v) => v,
+                    None => continue,
+                };
+
+                let target = ships
+                    .iter()
+                    .filter(|(other, _, other_is_player)| {
+                        *other != holder && *other_is_player != is_player
+                    })
+                    .filter_map(|(other, other_pos, _)| {
+                        let delta = wrap_delta(pos, *other_pos);
+                        let direction = Unit::from_complex(Complex::new(delta.x, delta.y));
+                        let angle = transform.rotation.angle_to(&direction);
+
+                        let in_cone = delta.norm() < guidance.range && angle.abs() < guidance.cone;
+                        in_cone.then_some((delta.norm(), *other))
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0))
+                    .map(|v| v.1);
+
+                weapon.acquire(target, current_time);
+            }
+        }
+    }
+
+    /// Steers homing missiles and detonates the ones close to their target.
+    pub(super) fn update_missiles(&mut self, to_remove: &mut Vec<EntityHolder>) {
+        let dt = get_frame_time() as f64;
+        let mut detonated = vec![];
+
+        {
+            let rigid_body_set = &mut *self.rigid_body_set.borrow_mut();
+
+            let positions = self
+                .entities
+                .iter()
+                .filter(|(_, v)| v.health.is_some())
+                .filter_map(|(holder, v)| Some((holder, v.pos(rigid_body_set)?)))
+                .collect::<HashMap<_, _>>();
+
+            for (holder, entity) in self.entities.iter_mut() {
+                let homing = match entity.projectile.and_then(|v| v.homing) {
+                    Some(v) => v,
+                    None => continue,
+                };
+                let target = positions.get(&homing.target).copied();
+
+                let rigid_body = match entity.get_rigid_body_mut(rigid_body_set) {
+                    Some(v) => v,
+                    None => continue,
+                };
+
+                if entity.update_homing(target, rigid_body, dt) == Some(true) {
+                    detonated.push(holder);
+                }
+            }
+        }
+
+        detonated.into_iter().for_each(|missile| {
+            self.detonate(missile, to_remove);
+        });
+    }
+
+    /// Damages everything in the blast radius of `missile`, except its shooter.
+    fn detonate(&mut self, missile: EntityHolder, to_remove: &mut Vec<EntityHolder>) -> Option<()> {
+        let entity = self.get_entity(&missile)?;
+        let projectile = entity.projectile?;
+        let blast_radius = projectile.homing?.guidance.blast_radius;
+
+        let pos = entity.pos(&self.rigid_body_set.borrow())?;
+
+        let victims = {
+            let rigid_body_set = &*self.rigid_body_set.borrow();
+
+            self.entities
+                .iter()
+                .filter(|(holder, v)| *holder != projectile.source && v.health.is_some())
+                .filter(|(holder, _)| !self.is_friendly_fire(Some(projectile.source), *holder))
+                .filter(|(_, v)| {
+                    v.pos(rigid_body_set)
+                        .is_some_and(|p| wrap_delta(pos, p).norm() < blast_radius)
+                })
+                .map(|(holder, _)| holder)
+                .collect::<Vec<_>>()
+        };
+
+        to_remove.push(missile);
+
+        victims.into_iter().for_each(|victim| {
+            let hit = Hit::new(projectile.damage, Some(pos)).by(projectile.source);
+            if self.apply_damage(victim, hit, to_remove).is_some() {
+                self.apply_effect(victim, projectile.effect.map(|v| v.by(projectile.source)));
+            }
+        });
+
+        Some(())
+    }
+
+    /// Casts a ray that continues on the other side of the world when it
+    /// leaves it. Returns how far it got, and what it hit.
+    fn cast_wrapped_ray(
+        &self,
+        origin: Point<Real>,
+        direction: Vector<Real>,
+        range: Real,
+        groups: InteractionGroups,
+        exclude: Option<ColliderHandle>,
+    ) -> (Real, Option<ColliderHandle>) {
+        let query_pipeline = self.query_pipeline.borrow();
+        let collider_set = &*self.collider_set.borrow();
+        let filter = |handle: ColliderHandle| Some(handle) != exclude;
+
+        let mut origin = origin;
+        let mut traveled = 0.0;
+
+        while traveled < range {
+            let step = distance_to_edge(origin, direction)
+                .max(MIN_RAY_STEP)
+                .min(range - traveled);
+            let ray = Ray::new(origin, direction);
+
+            if let Some((handle, toi)) =
+                query_pipeline.cast_ray(collider_set, &ray, step, true, groups, Some(&filter))
+            {
+                return (traveled + toi, Some(handle));
+            }
+
+            traveled += step;
+            origin = crop_to_world(origin + direction * step);
+        }
+
+        (range, None)
+    }
+
+    /// Ray casts every firing beam and damages what it hits, more the longer
+    /// it stays on the same target.
+    pub(super) fn update_beams(&mut self, current_time: f64, to_remove: &mut Vec<EntityHolder>) {
+        let dt = get_frame_time();
+
+        self.query_pipeline.borrow_mut().update(
+            &self.island_manager.borrow(),
+            &self.rigid_body_set.borrow(),
+            &self.collider_set.borrow(),
+        );
+
+        let shooters = self
+            .entities
+            .iter()
+            .filter(|(_, v)| v.weapons.iter().any(|v| v.beam.is_some()))
+            .map(|(holder, _)| holder)
+            .collect::<Vec<_>>();
+
+        let mut hits = vec![];
+
+        for shooter in shooters {
+            let entity = match self.get_entity(&shooter) {
+                Some(v) => v,
+                None => continue,
+            };
+            let transform = match entity.transform(&self.rigid_body_set.borrow()) {
+                Some(v) => v,
+                None => continue,
+            };
+            let fire_points = entity
+                .resource
+                .info
+                .fire_points()
+                .map(|v| v.get_point2s().clone())
+                .unwrap_or_default();
+            let own_collider = entity.physics.map(|v| v.collider_handle);
+            let direction = transform.rotation * Vector::x();
+
+            // cast first, then write back, the world is borrowed while casting
+            let results = entity
+                .weapons
+                .iter()
+                .map(|weapon| {
+                    let beam = weapon.beam?;
+                    let fire_point = fire_points.get(weapon.fire_point)?;
+
+                    if !weapon.beam_state.firing {
+                        return None;
+                    }
+
+                    let groups = weapon
+                        .projectile
+                        .info
+                        .collision_group()
+                        .copied()
+                        .unwrap_or(*Collision::from_str("beams"));
+                    let origin = transform.transform_point(fire_point);
+
+                    let (length, hit) =
+                        self.cast_wrapped_ray(origin, direction, beam.range, groups, own_collider);
+                    let target = hit.and_then(|v| self.get_holder_by_collider(v));
+
+                    Some((length, target, origin + direction * length))
+                })
+                .collect::<Vec<_>>();
+
+            let entity = match self.get_entity_mut(&shooter) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            for (weapon, result) in entity.weapons.iter_mut().zip(results) {
+                let beam = match weapon.beam {
+                    Some(v) => v,
+                    None => continue,
+                };
+                let state = &mut weapon.beam_state;
+
+                let (length, target, point) = match result {
+                    Some(v) => v,
+                    None => {
+                        state.hit(None, current_time);
+                        continue;
+                    }
+                };
+
+                state.length = length;
+                state.hit(target, current_time);
+
+                if let Some(target) = target {
+                    let multiplier = beam.damage_multiplier(current_time - state.on_target_since);
+                    let damage = beam.damage_per_second * multiplier;
+                    let hit = Hit::continuous(damage, dt, Some(point)).by(shooter);
+                    hits.push((target, hit));
+                }
+            }
+        }
+
+        hits.into_iter().for_each(|(target, hit)| {
+            self.apply_damage(target, hit, to_remove);
+        });
+    }
+}
@@ -0,0 +1,236 @@
+use macroquad::prelude::{get_time, rand};
+
+use crate::{
+    economy::commodity::CommodityId,
+    entity::{
+        cargo::{CargoLike, Stack},
+        entity::EntityHolder,
+        physics::PhysicsLike,
+    },
+    util::{screen::wrap_delta, station::Service},
+};
+
+use super::world::World;
+
+/// Contracts a station with missions has on offer at a time.
+const CONTRACT_OFFERS: usize = 3;
+const MIN_CONTRACT_AMOUNT: u32 = 2;
+const MAX_CONTRACT_AMOUNT: u32 = 6;
+/// Share of the value of the goods paid on delivery, on top of the distance.
+const CONTRACT_MARGIN: f32 = 0.4;
+const REWARD_PER_DISTANCE: f32 = 0.1;
+/// Gained with the faction of the station that handed out a contract.
+const CONTRACT_REPUTATION: f32 = 5.0;
+/// Lost with it when a contract runs out.
+const CONTRACT_FAILED: f32 = 5.0;
+/// Seconds to deliver, on top of the time it takes to fly there.
+const CONTRACT_TIME: f64 = 60.0;
+const CONTRACT_PACE: f64 = 50.0;
+
+/// Goods the player carries from one station to another for a reward. The
+/// origin hands them out against a deposit of what they're worth there, so
+/// selling them off never pays more than delivering them.
+#[derive(Debug, Clone, Copy)]
+pub struct Contract {
+    pub origin: EntityHolder,
+    pub destination: EntityHolder,
+    pub commodity: CommodityId,
+    pub amount: u32,
+    pub reward: u32,
+    /// Paid back with the reward.
+    pub deposit: u32,
+    /// Seconds from accepting it to its deadline.
+    pub time_limit: f64,
+    /// Set once accepted, the deposit is lost past it.
+    pub deadline: f64,
+}
+
+impl World {
+    /// Stations with a market, other than `station`.
+    fn destinations(&self, station: EntityHolder) -> Vec<EntityHolder> {
+        self.entities
+            .iter()
+            .filter(|(holder, v)| {
+                *holder != station && v.station.is_some_and(|v| v.market.is_some())
+            })
+            .map(|v| v.0)
+            .collect()
+    }
+
+    fn contract(&self, origin: EntityHolder, destination: EntityHolder) -> Option<Contract> {
+        let market = self.get_entity(&origin)?.station?.market?;
+        let commodities = self.economy.markets[market].traded().collect::<Vec<_>>();
+        let commodity = *commodities.get(rand::gen_range(0, commodities.len()))?;
+        let amount = rand::gen_range(MIN_CONTRACT_AMOUNT, MAX_CONTRACT_AMOUNT + 1);
+
+        let rigid_body_set = &*self.rigid_body_set.borrow();
+        let from = self.get_entity(&origin)?.pos(rigid_body_set)?;
+        let to = self.get_entity(&destination)?.pos(rigid_body_set)?;
+        let distance = wrap_delta(from, to).norm();
+
+        let value = self.economy.commodities[commodity].base_price * amount as f32;
+        let reward = value * CONTRACT_MARGIN + distance * REWARD_PER_DISTANCE;
+        let deposit = self.economy.buy_price(market, commodity, amount)?;
+
+        Some(Contract {
+            origin,
+            destination,
+            commodity,
+            amount,
+            reward: reward.round() as u32,
+            deposit: deposit.ceil() as u32,
+            time_limit: CONTRACT_TIME + distance as f64 / CONTRACT_PACE,
+            deadline: 0.0,
+        })
+    }
+
+    /// Replaces the contracts on offer with new ones from `station`.
+    pub(super) fn offer_contracts(&mut self, station: EntityHolder) -> Option<()> {
+        self.offers.clear();
+
+        if !self
+            .get_entity(&station)?
+            .station?
+            .spec
+            .offers(Service::Missions)
+        {
+            return None;
+        }
+
+        let destinations = self.destinations(station);
+        if destinations.is_empty() {
+            return None;
+        }
+
+        self.offers = (0..CONTRACT_OFFERS)
+            .filter_map(|_| {
+                let destination = destinations[rand::gen_range(0, destinations.len())];
+                self.contract(station, destination)
+            })
+            .collect();
+
+        Some(())
+    }
+
+    /// Takes on the contract on offer at `index`, if the goods fit in the
+    /// hold and the player can leave the deposit.
+    pub(super) fn accept_contract(&mut self, player: EntityHolder, index: usize) -> Option<()> {
+        let mut contract = *self.offers.get(index)?;
+        let market = self.get_entity(&contract.origin)?.station?.market?;
+
+        let player_entity = self.entities.get_mut(player)?;
+        let stack = Stack {
+            commodity: contract.commodity,
+            amount: contract.amount,
+        };
+
+        let inventory = player_entity.inventory.as_mut()?;
+        if inventory.credits < contract.deposit {
+            log::info!("not enough credits for the deposit");
+            return None;
+        }
+
+        if !player_entity.cargo.as_mut()?.add(stack) {
+            log::info!("no room in the hold for the contract");
+            return None;
+        }
+
+        player_entity.inventory.as_mut()?.credits -= contract.deposit;
+        player_entity.update_cargo_mass(&mut self.rigid_body_set.borrow_mut());
+
+        self.economy
+            .trade(market, contract.commodity, contract.amount as f32);
+        self.offers.remove(index);
+
+        contract.deadline = get_time() + contract.time_limit;
+        self.contracts.push(contract);
+
+        Some(())
+    }
+
+    /// Drops the contracts past their deadline, the player keeps the goods
+    /// but not the deposit.
+    pub(super) fn expire_contracts(&mut self, current_time: f64) {
+        let (expired, contracts) = self
+            .contracts
+            .iter()
+            .partition::<Vec<_>, _>(|v| v.deadline < current_time);
+        self.contracts = contracts;
+
+        for contract in expired {
+            log::info!("contract ran out, {} cr deposit lost", contract.deposit);
+
+            if let Some(faction) = self.get_entity(&contract.origin).and_then(|v| v.faction) {
+                self.change_reputation(faction, -CONTRACT_FAILED);
+            }
+        }
+    }
+
+    /// Pays out every contract that ends at `station` the player has the
+    /// goods for.
+    pub(super) fn complete_contracts(
+        &mut self,
+        player: EntityHolder,
+        station: EntityHolder,
+    ) -> Option<()> {
+        let market = self.get_entity(&station)?.station?.market;
+        let mut completed = vec![];
+
+        {
+            let player_entity = self.entities.get_mut(player)?;
+
+            for (i, contract) in self.contracts.iter().enumerate() {
+                if contract.destination != station {
+                    continue;
+                }
+
+                let cargo = player_entity.cargo.as_mut()?;
+                let carried = cargo
+                    .stacks
+                    .iter()
+                    .find(|v| v.commodity == contract.commodity)
+                    .map_or(0, |v| v.amount);
+
+                if carried < contract.amount {
+                    continue;
+                }
+
+                cargo.remove(contract.commodity, contract.amount);
+                player_entity.inventory.as_mut()?.credits += contract.reward + contract.deposit;
+                completed.push(i);
+            }
+
+            player_entity.update_cargo_mass(&mut self.rigid_body_set.borrow_mut());
+        }
+
+        for i in completed.into_iter().rev() {
+            let contract = self.contracts.remove(i);
+            log::info!("contract completed for {} credits", contract.reward);
+
+            if let Some(market) = market {
+                self.economy
+                    .trade(market, contract.commodity, -(contract.amount as f32));
+            }
+
+            if let Some(faction) = self.get_entity(&contract.origin).and_then(|v| v.faction) {
+                self.change_reputation(faction, CONTRACT_REPUTATION);
+            }
+        }
+
+        Some(())
+    }
+
+    /// What a contract asks for, to show the player.
+    pub fn describe_contract(&self, contract: &Contract) -> Option<String> {
+        let destination = self.get_entity(&contract.destination)?.station?.spec;
+
+        Some(format!(
+            "{} {} to {}, {} cr for {} cr deposit",
+            contract.amount,
+            self.economy.commodities[contract.commodity].name,
+            destination.name,
+            contract.reward,
+            contract.deposit
+        ))
+    }
+}
@@ -0,0 +1,283 @@
+use macroquad::prelude::{rand, Color};
+use rapier2d::math::{Isometry, Point, Real, Vector};
+
+use crate::{
+    economy::simulation::Route,
+    entity::{
+        ai::Ai,
+        animation::Animation,
+        cargo::{CargoHold, Stack},
+        convoy::{Guard, Merchant},
+        drawable::Drawable,
+        entity::{EntityBuilder, EntityHolder},
+        health::{Health, HealthLike},
+        physics::PhysicsLike,
+        shield::Shields,
+        thrusters::Thrusters,
+        weapon::Weapon,
+    },
+    util::screen::wrap_delta,
+    FACTIONS, MODULES, SHIP,
+};
+
+use super::{
+    world::{World, ENEMY_COOLDOWN_FACTOR},
+    world_mutator::WorldMutator,
+};
+
+/// Seconds between two convoys setting out.
+const CONVOY_INTERVAL: f64 = 20.0;
+const MAX_CONVOYS: usize = 3;
+/// Most units a merchant loads, as long as the origin has four times that.
+const CONVOY_CARGO: u32 = 20;
+/// Routes a convoy picks from, the most profitable ones.
+const ROUTE_CHOICES: usize = 3;
+/// Route value, in credits at the destination, that earns the convoy a guard.
+const VALUE_PER_GUARD: f32 = 600.0;
+const MAX_GUARDS: u32 = 3;
+
+const MERCHANT_FACTION: &str = "consortium";
+const GUARD_FACTION: &str = "security";
+const MERCHANT_ENGINE: &str = "engine_hauler";
+const GUARD_ENGINE: &str = "engine_standard";
+const MERCHANT_HULL: f32 = 60.0;
+const GUARD_HULL: f32 = 40.0;
+const GUARD_SHIELDS: f32 = 20.0;
+/// Merchants set out this far from the origin, toward their destination.
+const DEPARTURE_DISTANCE: Real = 350.0;
+const GUARD_SPACING: Real = 80.0;
+/// Merchants this close to their destination have arrived.
+const ARRIVAL_DISTANCE: Real = 350.0;
+const MERCHANT_TINT: Color = Color::new(0.9, 0.85, 0.6, 1.0);
+
+impl World {
+    /// The station entity trading on `market`.
+    fn station_of_market(&self, market: usize) -> Option<EntityHolder> {
+        self.entities
+            .iter()
+            .find(|(_, v)| v.station.is_some_and(|v| v.market == Some(market)))
+            .map(|v| v.0)
+    }
+
+    /// Sets out a new convoy every `CONVOY_INTERVAL`, on one of the most
+    /// profitable routes of the economy.
+    pub(super) fn update_convoys(
+        &mut self,
+        current_time: f64,
+        to_remove: &mut Vec<EntityHolder>,
+    ) -> Option<()> {
+        self.arrive(to_remove);
+
+        if current_time - self.last_convoy < CONVOY_INTERVAL {
+            return None;
+        }
+        self.last_convoy = current_time;
+
+        let convoys = self
+            .entities
+            .iter()
+            .filter(|(_, v)| v.merchant.is_some())
+            .count();
+        if convoys >= MAX_CONVOYS {
+            return None;
+        }
+
+        let routes = self.economy.routes(CONVOY_CARGO);
+        let choices = routes.len().min(ROUTE_CHOICES);
+        if choices == 0 {
+            return None;
+        }
+
+        self.spawn_convoy(routes[rand::gen_range(0, choices)])
+    }
+
+    fn spawn_convoy(&mut self, route: Route) -> Option<()> {
+        let origin = self.station_of_market(route.from)?;
+        let destination = self.station_of_market(route.to)?;
+
+        let supply = self.economy.markets[route.from]
+            .stock(route.commodity)?
+            .supply;
+        let amount = CONVOY_CARGO.min((supply / 4.0) as u32);
+        if amount == 0 {
+            return None;
+        }
+
+        let thrusters = MODULES
+            .get(MERCHANT_ENGINE)
+            .and_then(Thrusters::from_module)?;
+        let value = self.economy.sell_price(route.to, route.commodity, amount)?;
+        let guards = ((value / VALUE_PER_GUARD) as u32).min(MAX_GUARDS);
+
+        let (from, to) = {
+            let rigid_body_set = &*self.rigid_body_set.borrow();
+            (
+                self.get_entity(&origin)?.pos(rigid_body_set)?,
+                self.get_entity(&destination)?.pos(rigid_body_set)?,
+            )
+        };
+        let heading = wrap_delta(from, to).normalize();
+        let start = from + heading * DEPARTURE_DISTANCE;
+
+        // the goods leave the origin now, whether they arrive or not
+        self.economy
+            .trade(route.from, route.commodity, amount as f32);
+
+        let stack = Stack {
+            commodity: route.commodity,
+            amount,
+        };
+        let cargo = CargoHold {
+            capacity: stack.volume(),
+            stacks: vec![stack],
+        };
+
+        let mut builder = EntityBuilder::new(&SHIP);
+        builder
+            .drawable(Drawable::from_resource(&SHIP)?.tint(MERCHANT_TINT))
+            .animation(Animation::default())
+            .health(Health::new(MERCHANT_HULL))
+            .ai(Ai::merchant())
+            .thrusters(thrusters)
+            .cargo(cargo)
+            .merchant(Merchant {
+                destination,
+                attacked: false,
+            });
+        if let Some(faction) = FACTIONS.get(MERCHANT_FACTION) {
+            builder.faction(faction);
+        }
+
+        let merchant = self.handle_mutator(place(&builder, start))?;
+
+        log::info!(
+            "convoy of {} {} with {} guards",
+            amount,
+            self.economy.commodities[route.commodity].name,
+            guards
+        );
+
+        let side = Vector::new(-heading.y, heading.x);
+        for i in 0..guards {
+            let offset = if i % 2 == 0 { 1.0 } else { -1.0 } * (i / 2 + 1) as Real;
+            let position = start - heading * GUARD_SPACING + side * GUARD_SPACING * offset;
+
+            self.spawn_guard(merchant, origin, position);
+        }
+
+        Some(())
+    }
+
+    fn spawn_guard(
+        &mut self,
+        leader: EntityHolder,
+        home: EntityHolder,
+        position: Point<Real>,
+    ) -> Option<()> {
+        let faction = FACTIONS.get(GUARD_FACTION)?;
+        let thrusters = MODULES.get(GUARD_ENGINE).and_then(Thrusters::from_module)?;
+        let weapon = Weapon::burst_cannon(0);
+        let weapon = Weapon {
+            cooldown: weapon.cooldown * ENEMY_COOLDOWN_FACTOR,
+            ..weapon
+        };
+
+        let mut builder = EntityBuilder::new(&SHIP);
+        builder
+            .drawable(Drawable::from_resource(&SHIP)?.tint(faction.color))
+            .animation(Animation::default())
+            .health(Health::new(GUARD_HULL).shields(Shields::new(GUARD_SHIELDS, 4.0, 3.0)))
+            .ai(Ai::escort())
+            .thrusters(thrusters)
+            .weapon(weapon)
+            .faction(faction)
+            .guard(Guard {
+                leader: Some(leader),
+                home,
+            });
+
+        self.handle_mutator(place(&builder, position))?;
+
+        Some(())
+    }
+
+    /// Merchants that made it unload at their destination, which restocks
+    /// its market, and leave with their guards. Guards without a merchant
+    /// leave once they're home.
+    fn arrive(&mut self, to_remove: &mut Vec<EntityHolder>) -> Option<()> {
+        let rigid_body_set = &*self.rigid_body_set.borrow();
+
+        let home = self.entities.iter().filter_map(|(holder, v)| {
+            let guard = v.guard.filter(|v| v.leader.is_none())?;
+            let station = self.get_entity(&guard.home)?;
+            let distance = wrap_delta(v.pos(rigid_body_set)?, station.pos(rigid_body_set)?).norm();
+
+            (distance < ARRIVAL_DISTANCE).then_some(holder)
+        });
+        to_remove.extend(home);
+
+        let arrived = self
+            .entities
+            .iter()
+            .filter(|(_, v)| !v.is_destroyed())
+            .filter_map(|(holder, v)| {
+                let destination = self.get_entity(&v.merchant?.destination)?;
+                let distance =
+                    wrap_delta(v.pos(rigid_body_set)?, destination.pos(rigid_body_set)?).norm();
+
+                (distance < ARRIVAL_DISTANCE).then_some((holder, destination.station?.market?))
+            })
+            .collect::<Vec<_>>();
+
+        for (merchant, market) in arrived {
+            let stacks = self.get_entity(&merchant)?.cargo.as_ref()?.stacks.clone();
+            stacks.iter().for_each(|stack| {
+                self.economy
+                    .trade(market, stack.commodity, -(stack.amount as f32));
+            });
+
+            to_remove.push(merchant);
+            to_remove.extend(
+                self.entities
+                    .iter()
+                    .filter(|(_, v)| v.guard.is_some_and(|v| v.leader == Some(merchant)))
+                    .map(|v| v.0),
+            );
+        }
+
+        Some(())
+    }
+
+    /// Sends the guards of a merchant that's gone home.
+    pub(super) fn disband_convoy(&mut self, merchant: EntityHolder) {
+        self.entities
+            .iter_mut()
+            .filter_map(|(_, v)| v.guard.as_mut())
+            .filter(|v| v.leader == Some(merchant))
+            .for_each(|v| v.leader = None);
+    }
+
+    /// The player fired on `target`, if it's part of a convoy its guards
+    /// fight back.
+    pub(super) fn alarm_convoy(&mut self, target: EntityHolder) -> Option<()> {
+        let target_entity = self.get_entity(&target)?;
+        let leader = match target_entity.guard {
+            Some(guard) => guard.leader?,
+            None => target,
+        };
+
+        self.get_entity_mut(&leader)?.merchant.as_mut()?.attacked = true;
+
+        Some(())
+    }
+}
+
+fn place(builder: &EntityBuilder, position: Point<Real>) -> WorldMutator {
+    builder.build_mutator(Box::new(move |entity, rigid_body_set| {
+        entity
+            .get_rigid_body_mut(rigid_body_set)?
+            .set_position(Isometry::translation(position.x, position.y), false);
+
+        None
+    }))
+}
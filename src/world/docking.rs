@@ -0,0 +1,178 @@
+use rapier2d::{
+    math::{Point, Real, Vector},
+    prelude::IntersectionEvent,
+};
+
+use crate::{
+    entity::{
+        docking::{Docked, DockingLike},
+        entity::EntityHolder,
+        hold::Hold,
+        physics::PhysicsLike,
+    },
+    util::{screen::wrap_delta, station::Service},
+};
+
+use super::{event::GameEvent, world::World};
+
+/// Faster than this, and the docking clamps can't catch the ship.
+const DOCKING_SPEED: Real = 40.0;
+const DOCKING_TIME: f64 = 2.0;
+/// Nobody fights this close to the station the player is docked at.
+const PEACE_RADIUS: Real = 900.0;
+
+impl World {
+    /// Keeps track of the docking zone the player is in, from the overlaps
+    /// of their ship with the docking sensors of stations.
+    pub(super) fn dock_zone(
+        &mut self,
+        player: EntityHolder,
+        intersection_event: IntersectionEvent,
+    ) -> Option<()> {
+        let a = self.get_holder_by_collider(intersection_event.collider1)?;
+        let b = self.get_holder_by_collider(intersection_event.collider2)?;
+
+        let (station, dock) = match (a == player, b == player) {
+            (true, false) => (b, intersection_event.collider2),
+            (false, true) => (a, intersection_event.collider1),
+            _ => return None,
+        };
+
+        if self.get_entity(&station)?.physics?.dock_handle != Some(dock) {
+            return None;
+        }
+
+        let state = self.get_entity_mut(&player)?.player.as_mut()?;
+
+        if intersection_event.intersecting {
+            state.dock_zone = Some(station);
+        } else if state.dock_zone == Some(station) {
+            state.dock_zone = None;
+        }
+
+        Some(())
+    }
+
+    /// Stations of factions hostile to the player turn them away.
+    pub fn is_refused(&self, station: EntityHolder) -> bool {
+        self.get_entity(&station)
+            .and_then(|v| v.faction)
+            .is_some_and(|v| self.reputation.is_hostile(v))
+    }
+
+    /// The station whose docking zone the player holds still in, if it lets
+    /// them dock.
+    fn docking_station(&self, player: EntityHolder) -> Option<EntityHolder> {
+        let player_entity = self.get_entity(&player)?;
+        let station = player_entity.player?.dock_zone?;
+        let speed = player_entity
+            .velocity(&self.rigid_body_set.borrow())?
+            .norm();
+
+        (speed < DOCKING_SPEED && !self.is_refused(station)).then_some(station)
+    }
+
+    /// Docks the player once they've held still in a docking zone for long
+    /// enough. Docked, their ship is held in place and the station takes
+    /// their commands.
+    pub(super) fn update_docking(&mut self, player: EntityHolder, current_time: f64) -> Option<()> {
+        if let Some(docked) = self.get_entity(&player)?.player?.docked {
+            return self.update_docked(player, docked);
+        }
+
+        let station = self.docking_station(player);
+        let state = self.get_entity_mut(&player)?.player.as_mut()?;
+
+        state.docking = Hold::follow(state.docking, station, current_time);
+
+        let docking = state.docking?;
+        if !docking.is_done(current_time, DOCKING_TIME) {
+            return Some(());
+        }
+
+        state.docking = None;
+        self.dock(player, docking.target)
+    }
+
+    fn dock(&mut self, player: EntityHolder, station: EntityHolder) -> Option<()> {
+        let spec = self.get_entity(&station)?.station?.spec;
+        let service = spec.services.first().copied().unwrap_or(Service::Market);
+
+        let state = self.get_entity_mut(&player)?.player.as_mut()?;
+
+        state.docked = Some(Docked::new(station, service));
+        // the number keys pick services now, not what to do with a boarded ship
+        state.boarding = None;
+        state.boarded = None;
+
+        self.complete_contracts(player, station);
+        self.offer_contracts(station);
+
+        self.events.push(GameEvent::Docked { station: spec });
+
+        Some(())
+    }
+
+    fn update_docked(&mut self, player: EntityHolder, docked: Docked) -> Option<()> {
+        {
+            let rigid_body_set = &mut *self.rigid_body_set.borrow_mut();
+            let rigid_body = self
+                .get_entity(&player)?
+                .get_rigid_body_mut(rigid_body_set)?;
+
+            rigid_body.set_linvel(Vector::zeros(), true);
+            rigid_body.set_angvel(0.0, true);
+        }
+
+        let command = self.get_entity(&player)?.station_command()?;
+
+        self.station_command(player, docked, command)
+    }
+
+    /// Leaves the station. It takes leaving its docking zone to dock again.
+    pub(super) fn undock(&mut self, player: EntityHolder) -> Option<()> {
+        let state = self.get_entity_mut(&player)?.player.as_mut()?;
+
+        state.docked = None;
+        state.dock_zone = None;
+        self.offers.clear();
+
+        Some(())
+    }
+
+    /// How far along the player is docking, from 0 to 1.
+    pub fn docking_progress(&self, current_time: f64) -> Option<f32> {
+        let docking = self.get_entity(&self.player?)?.player?.docking?;
+
+        Some(docking.progress(current_time, DOCKING_TIME))
+    }
+
+    /// Around the station the player is docked at.
+    pub(super) fn peace_zone(&self) -> Option<Point<Real>> {
+        let player_entity = self.get_entity(&self.player?)?;
+        let station = player_entity.player?.docked?.station;
+
+        self.get_entity(&station)?
+            .pos(&self.rigid_body_set.borrow())
+    }
+
+    /// Whether `pos` is inside `peace_zone`, so nobody fights there.
+    pub(super) fn is_peaceful(peace_zone: Option<Point<Real>>, pos: Point<Real>) -> bool {
+        peace_zone.is_some_and(|v| wrap_delta(v, pos).norm() < PEACE_RADIUS)
+    }
+
+    /// Ships don't take damage in the peace zone, nor does the docked player.
+    pub(super) fn is_sheltered(&self, target: EntityHolder) -> bool {
+        let entity = match self.get_entity(&target) {
+            Some(v) => v,
+            None => return false,
+        };
+
+        if entity.is_docked() {
+            return true;
+        }
+
+        let pos = entity.pos(&self.rigid_body_set.borrow());
+        pos.is_some_and(|v| Self::is_peaceful(self.peace_zone(), v))
+    }
+}
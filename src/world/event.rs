@@ -0,0 +1,24 @@
+use crate::{
+    entity::pickup::PickupKind,
+    util::{faction::Faction, station::StationSpec},
+};
+
+/// Things that happened in the world during an update that systems outside of
+/// it (camera, post processing, sound...) may want to react to.
+#[derive(Debug, Clone, Copy)]
+pub enum GameEvent {
+    PlayerHit {
+        strength: f32,
+    },
+    PickedUp {
+        kind: PickupKind,
+        amount: u32,
+    },
+    ReputationChanged {
+        faction: &'static Faction,
+        change: f32,
+    },
+    Docked {
+        station: &'static StationSpec,
+    },
+}
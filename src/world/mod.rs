@@ -1,2 +1,11 @@
+pub mod boarding;
+pub mod combat;
+pub mod contract;
+pub mod convoy;
+pub mod docking;
+pub mod event;
+pub mod pickup;
+pub mod reputation;
+pub mod station;
 pub mod world;
 pub mod world_mutator;
@@ -0,0 +1,122 @@
+use macroquad::prelude::{get_frame_time, get_time};
+use rapier2d::math::{Point, Real};
+
+use crate::{
+    entity::{
+        cargo::CargoLike, entity::EntityHolder, inventory::InventoryLike, physics::PhysicsLike,
+    },
+    util::screen::wrap_delta,
+};
+
+use super::{event::GameEvent, world::World};
+
+/// Pickups closer than this to the player drift toward them.
+const MAGNET_RADIUS: Real = 250.0;
+/// Speed at the edge of the magnet radius, it grows toward the player.
+const MAGNET_SPEED: Real = 150.0;
+const MAGNET_MAX_SPEED: Real = 600.0;
+/// How fast a pickup turns its velocity toward the player, per second.
+const MAGNET_PULL: Real = 8.0;
+
+impl World {
+    /// Pulls the pickups around the player toward them, across world edges.
+    pub(super) fn update_magnet(&mut self, player: EntityHolder) -> Option<()> {
+        let dt = get_frame_time();
+        let rigid_body_set = &mut *self.rigid_body_set.borrow_mut();
+        let pos = self.entities.get(player)?.pos(rigid_body_set)?;
+        let current_time = get_time();
+
+        for (_, entity) in self.entities.iter_mut() {
+            if !entity
+                .pickup
+                .is_some_and(|v| v.is_collectable(current_time))
+            {
+                continue;
+            }
+
+            let rigid_body = match entity.get_rigid_body_mut(rigid_body_set) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let delta = wrap_delta(Point::from(*rigid_body.translation()), pos);
+            let distance = delta.norm();
+
+            if distance >= MAGNET_RADIUS || distance <= Real::EPSILON {
+                continue;
+            }
+
+            let speed = (MAGNET_SPEED * MAGNET_RADIUS / distance).min(MAGNET_MAX_SPEED);
+            let target = delta / distance * speed;
+            let velocity = rigid_body
+                .linvel()
+                .lerp(&target, (MAGNET_PULL * dt).min(1.0));
+
+            rigid_body.set_linvel(velocity, true);
+        }
+
+        Some(())
+    }
+
+    /// Credits the pickups the player overlaps to their inventory or cargo
+    /// hold, and queues them for removal. Pickups refused by a full hold stay
+    /// until there's room.
+    pub(super) fn collect(
+        &mut self,
+        player: EntityHolder,
+        to_remove: &mut Vec<EntityHolder>,
+    ) -> Option<()> {
+        let collider = self.get_entity(&player)?.physics?.collider_handle;
+
+        let overlapping = self
+            .narrow_phase
+            .borrow()
+            .intersections_with(collider)
+            .filter(|v| v.2)
+            .filter_map(|(a, b, _)| {
+                let other = if a == collider { b } else { a };
+                self.get_holder_by_collider(other)
+            })
+            .collect::<Vec<_>>();
+
+        overlapping.into_iter().for_each(|pickup| {
+            self.collect_pickup(player, pickup, to_remove);
+        });
+
+        Some(())
+    }
+
+    fn collect_pickup(
+        &mut self,
+        player: EntityHolder,
+        pickup: EntityHolder,
+        to_remove: &mut Vec<EntityHolder>,
+    ) -> Option<()> {
+        // it may have expired this frame
+        if to_remove.contains(&pickup) {
+            return None;
+        }
+
+        let found = self.get_entity(&pickup)?.pickup?;
+        if !found.is_collectable(get_time()) {
+            return None;
+        }
+
+        let player_entity = self.entities.get_mut(player)?;
+
+        // full holds leave cargo floating
+        if !player_entity.take(found.kind, found.amount) {
+            return None;
+        }
+
+        player_entity.update_cargo_mass(&mut self.rigid_body_set.borrow_mut());
+        to_remove.push(pickup);
+
+        self.events.push(GameEvent::PickedUp {
+            kind: found.kind,
+            amount: found.amount,
+        });
+
+        Some(())
+    }
+}
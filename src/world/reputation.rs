@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use crate::{
+    util::faction::{Faction, Factions},
+    FACTIONS,
+};
+
+use super::{event::GameEvent, world::World};
+
+const MIN_REPUTATION: f32 = -100.0;
+const MAX_REPUTATION: f32 = 100.0;
+
+/// Below this a faction's ships attack the player on sight.
+const HOSTILE_BELOW: f32 = -25.0;
+const FRIENDLY_ABOVE: f32 = 25.0;
+const ALLIED_ABOVE: f32 = 60.0;
+
+/// Lost per point of damage dealt to a faction's ship.
+const ATTACK_PER_DAMAGE: f32 = 0.1;
+const DESTROYED: f32 = 15.0;
+/// Gained with every rival of a faction the player destroys a ship of.
+const RIVAL_DESTROYED: f32 = 5.0;
+
+/// Scales the spread between what stations charge the player and what they
+/// pay them.
+const NEUTRAL_SPREAD: f32 = 1.0;
+const FRIENDLY_SPREAD: f32 = 0.75;
+const ALLIED_SPREAD: f32 = 0.5;
+const HOSTILE_SPREAD: f32 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Standing {
+    Hostile,
+    Neutral,
+    Friendly,
+    Allied,
+}
+
+impl Standing {
+    fn from_reputation(reputation: f32) -> Self {
+        if reputation < HOSTILE_BELOW {
+            Standing::Hostile
+        } else if reputation > ALLIED_ABOVE {
+            Standing::Allied
+        } else if reputation > FRIENDLY_ABOVE {
+            Standing::Friendly
+        } else {
+            Standing::Neutral
+        }
+    }
+
+    /// Scales the spread stations of the faction trade at.
+    pub fn spread_factor(self) -> f32 {
+        match self {
+            Standing::Hostile => HOSTILE_SPREAD,
+            Standing::Neutral => NEUTRAL_SPREAD,
+            Standing::Friendly => FRIENDLY_SPREAD,
+            Standing::Allied => ALLIED_SPREAD,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Standing::Hostile => "HOSTILE",
+            Standing::Neutral => "NEUTRAL",
+            Standing::Friendly => "FRIENDLY",
+            Standing::Allied => "ALLIED",
+        }
+    }
+}
+
+/// How every faction feels about the player.
+pub struct Reputation {
+    scores: HashMap<String, f32>,
+}
+
+impl Reputation {
+    pub fn new(factions: &Factions) -> Self {
+        Self {
+            scores: factions
+                .factions
+                .iter()
+                .map(|v| (v.id.clone(), v.reputation))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, faction: &Faction) -> f32 {
+        self.scores
+            .get(&faction.id)
+            .copied()
+            .unwrap_or(faction.reputation)
+    }
+
+    pub fn standing(&self, faction: &Faction) -> Standing {
+        Standing::from_reputation(self.get(faction))
+    }
+
+    pub fn is_hostile(&self, faction: &Faction) -> bool {
+        self.standing(faction) == Standing::Hostile
+    }
+
+    /// Returns the change after clamping.
+    fn change(&mut self, faction: &Faction, amount: f32) -> f32 {
+        let before = self.get(faction);
+        let after = (before + amount).clamp(MIN_REPUTATION, MAX_REPUTATION);
+        self.scores.insert(faction.id.clone(), after);
+
+        after - before
+    }
+}
+
+impl World {
+    pub(super) fn change_reputation(&mut self, faction: &'static Faction, amount: f32) {
+        let was_hostile = self.reputation.is_hostile(faction);
+        let change = self.reputation.change(faction, amount);
+
+        if change == 0.0 {
+            return;
+        }
+
+        let standing = self.reputation.standing(faction);
+        if was_hostile != (standing == Standing::Hostile) {
+            log::info!("{} is now {}", faction.name, standing.name());
+        }
+
+        self.events
+            .push(GameEvent::ReputationChanged { faction, change });
+    }
+
+    /// The player damaged a ship of `faction`, and maybe destroyed it.
+    pub(super) fn player_attacked(
+        &mut self,
+        faction: &'static Faction,
+        damage: f32,
+        destroyed: bool,
+    ) {
+        self.change_reputation(faction, -damage * ATTACK_PER_DAMAGE);
+
+        if destroyed {
+            self.player_destroyed(faction);
+        }
+    }
+
+    /// Also pleases the rivals of `faction`.
+    pub(super) fn player_destroyed(&mut self, faction: &'static Faction) {
+        self.change_reputation(faction, -DESTROYED);
+
+        faction
+            .rivals
+            .iter()
+            .filter_map(|v| FACTIONS.get(v))
+            .for_each(|rival| self.change_reputation(rival, RIVAL_DESTROYED));
+    }
+}
@@ -0,0 +1,476 @@
+use macroquad::prelude::get_time;
+use rapier2d::math::Isometry;
+
+use crate::{
+    economy::{commodity::CommodityId, market::MarketId},
+    entity::{
+        cargo::{CargoLike, Stack},
+        docking::{Docked, StationCommand},
+        drawable::Drawable,
+        entity::{EntityBuilder, EntityHolder},
+        loadout::LoadoutLike,
+        physics::PhysicsLike,
+        station::Station,
+    },
+    util::{
+        module::Module,
+        station::{Service, Stations},
+    },
+    FACTIONS, MODULES, STATION,
+};
+
+use super::world::World;
+
+/// Gained with a station's faction per credit traded at it.
+const TRADE_REPUTATION: f32 = 0.002;
+/// Most trading at one station earns per `TRADE_WINDOW` seconds, so going
+/// back and forth over the counter can't buy more than that.
+const MAX_TRADE_REPUTATION: f32 = 1.0;
+const TRADE_WINDOW: f64 = 300.0;
+/// What a shipyard pays for the module it takes out, as a share of its price.
+const TRADE_IN: f32 = 0.5;
+const HULL_PRICE: f32 = 3.0;
+const AMMO_PRICE: u32 = 20;
+const REPAIR_KIT_PRICE: u32 = 120;
+
+/// What the repair service sells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Supply {
+    Hull,
+    Ammo,
+    RepairKit,
+}
+
+impl Supply {
+    const ALL: [Supply; 3] = [Supply::Hull, Supply::Ammo, Supply::RepairKit];
+}
+
+impl World {
+    /// Places every station of the catalogue, run once the world is set up.
+    pub fn spawn_stations(&mut self, stations: &'static Stations) {
+        for spec in stations.stations.iter() {
+            let faction = spec.faction.as_deref().and_then(|v| FACTIONS.get(v));
+            let market = spec
+                .market
+                .as_deref()
+                .and_then(|v| self.economy.market_id(v));
+
+            if spec.market.is_some() && market.is_none() {
+                log::warn!("{} has unknown market {:?}", spec.id, spec.market);
+            }
+
+            let mut drawable = match Drawable::from_resource(&STATION) {
+                Some(v) => v,
+                None => continue,
+            };
+            if let Some(faction) = faction {
+                drawable = drawable.tint(faction.color);
+            }
+
+            let mut builder = EntityBuilder::new(&STATION);
+            builder.drawable(drawable).station(Station { spec, market });
+            if let Some(faction) = faction {
+                builder.faction(faction);
+            }
+
+            let [x, y] = spec.position;
+            self.handle_mutator(
+                builder.build_mutator(Box::new(move |entity, rigid_body_set| {
+                    entity
+                        .get_rigid_body_mut(rigid_body_set)?
+                        .set_position(Isometry::translation(x, y), false);
+
+                    None
+                })),
+            );
+        }
+    }
+
+    fn station_market(&self, station: EntityHolder) -> Option<MarketId> {
+        self.get_entity(&station)?.station?.market
+    }
+
+    /// Scales the spread `station` trades at with the player, by their
+    /// standing with its faction.
+    fn spread_factor(&self, station: EntityHolder) -> f32 {
+        self.get_entity(&station)
+            .and_then(|v| v.faction)
+            .map_or(1.0, |v| self.reputation.standing(v).spread_factor())
+    }
+
+    fn market_commodities(&self, station: EntityHolder) -> Vec<CommodityId> {
+        self.station_market(station)
+            .map(|v| self.economy.markets[v].traded().collect())
+            .unwrap_or_default()
+    }
+
+    fn unit_buy_price(&self, station: EntityHolder, commodity: CommodityId) -> Option<u32> {
+        let market = self.station_market(station)?;
+        let price =
+            self.economy
+                .buy_price_spread(market, commodity, 1, self.spread_factor(station))?;
+
+        Some(price.ceil() as u32)
+    }
+
+    fn unit_sell_price(&self, station: EntityHolder, commodity: CommodityId) -> Option<u32> {
+        let market = self.station_market(station)?;
+        let price =
+            self.economy
+                .sell_price_spread(market, commodity, 1, self.spread_factor(station))?;
+
+        Some(price.floor() as u32)
+    }
+
+    /// Modules of the catalogue that fit `slot` of the player's ship.
+    fn shipyard_offers(&self, slot: usize) -> Vec<&'static Module> {
+        let kind = self
+            .player
+            .and_then(|v| self.get_entity(&v))
+            .and_then(|v| v.resource.info.slots().get(slot).copied())
+            .map(|v| v.kind);
+
+        MODULES
+            .modules
+            .iter()
+            .filter(|v| Some(v.kind) == kind)
+            .collect()
+    }
+
+    fn installed_module(&self, slot: usize) -> Option<&'static Module> {
+        let player_entity = self.get_entity(&self.player?)?;
+
+        *player_entity.loadout.as_ref()?.modules.get(slot)?
+    }
+
+    /// What swapping the module in `slot` for `module` costs, after the
+    /// trade in.
+    fn module_cost(&self, slot: usize, module: &Module) -> u32 {
+        let trade_in = self
+            .installed_module(slot)
+            .map_or(0, |v| (v.price as f32 * TRADE_IN) as u32);
+
+        module.price.saturating_sub(trade_in)
+    }
+
+    fn supply_cost(&self, supply: Supply) -> Option<u32> {
+        let cost = match supply {
+            Supply::Hull => {
+                let health = self.get_entity(&self.player?)?.health?;
+                ((health.max_hull - health.hull) * HULL_PRICE).ceil() as u32
+            }
+            Supply::Ammo => AMMO_PRICE,
+            Supply::RepairKit => REPAIR_KIT_PRICE,
+        };
+
+        Some(cost)
+    }
+
+    /// The lines of the service the player has open, one per selectable row.
+    pub fn service_rows(&self, docked: Docked) -> Vec<String> {
+        let station = docked.station;
+
+        if !self
+            .get_entity(&station)
+            .and_then(|v| v.station)
+            .is_some_and(|v| v.spec.offers(docked.service))
+        {
+            return Vec::new();
+        }
+
+        match docked.service {
+            Service::Market => {
+                let cargo = self
+                    .player
+                    .and_then(|v| self.get_entity(&v))
+                    .and_then(|v| v.cargo.as_ref());
+                let price = |v: Option<u32>| v.map_or("-".to_string(), |v| v.to_string());
+
+                self.market_commodities(station)
+                    .into_iter()
+                    .map(|commodity| {
+                        let held = cargo
+                            .and_then(|v| v.stacks.iter().find(|v| v.commodity == commodity))
+                            .map_or(0, |v| v.amount);
+                        let supply = self
+                            .station_market(station)
+                            .and_then(|v| self.economy.markets[v].stock(commodity))
+                            .map_or(0.0, |v| v.supply);
+
+                        format!(
+                            "{:<12} buy {:>5}  sell {:>5}  stock {:>4.0}  hold {}",
+                            self.economy.commodities[commodity].name,
+                            price(self.unit_buy_price(station, commodity)),
+                            price(self.unit_sell_price(station, commodity)),
+                            supply,
+                            held
+                        )
+                    })
+                    .collect()
+            }
+            Service::Shipyard => {
+                let slots = self
+                    .player
+                    .and_then(|v| self.get_entity(&v))
+                    .map_or(0, |v| v.resource.info.slots().len());
+
+                (0..slots)
+                    .map(|slot| {
+                        let installed = self.installed_module(slot).map_or("empty", |v| &v.name);
+
+                        if slot != docked.selection {
+                            return installed.to_string();
+                        }
+
+                        let offers = self.shipyard_offers(slot);
+                        match offers.get(docked.offer % offers.len().max(1)) {
+                            Some(offer) => format!(
+                                "{} -> < {} > {} cr",
+                                installed,
+                                offer.name,
+                                self.module_cost(slot, offer)
+                            ),
+                            None => installed.to_string(),
+                        }
+                    })
+                    .collect()
+            }
+            Service::Repair => Supply::ALL
+                .iter()
+                .map(|supply| {
+                    let name = match supply {
+                        Supply::Hull => "Repair hull",
+                        Supply::Ammo => "Ammo",
+                        Supply::RepairKit => "Repair kit",
+                    };
+                    let cost = self.supply_cost(*supply).unwrap_or(0);
+
+                    format!("{:<12} {} cr", name, cost)
+                })
+                .collect(),
+            Service::Missions => self
+                .offers
+                .iter()
+                .filter_map(|v| {
+                    Some(format!(
+                        "{}, {:.0}s",
+                        self.describe_contract(v)?,
+                        v.time_limit
+                    ))
+                })
+                .collect(),
+        }
+    }
+
+    /// Carries out what the docked player asked the station for.
+    pub(super) fn station_command(
+        &mut self,
+        player: EntityHolder,
+        docked: Docked,
+        command: StationCommand,
+    ) -> Option<()> {
+        let spec = self.get_entity(&docked.station)?.station?.spec;
+        let rows = self.service_rows(docked).len();
+        let offers = self.shipyard_offers(docked.selection).len().max(1);
+
+        let mut docked = docked;
+        match command {
+            StationCommand::Open(service) if spec.offers(service) => {
+                docked = Docked::new(docked.station, service)
+            }
+            StationCommand::Open(_) => {}
+            StationCommand::Up => {
+                docked.selection = docked.selection.saturating_sub(1);
+                docked.offer = 0;
+            }
+            StationCommand::Down => {
+                docked.selection = (docked.selection + 1).min(rows.saturating_sub(1));
+                docked.offer = 0;
+            }
+            StationCommand::Previous => docked.offer = (docked.offer + offers - 1) % offers,
+            StationCommand::Next => docked.offer = (docked.offer + 1) % offers,
+            StationCommand::Undock => return self.undock(player),
+            StationCommand::Buy | StationCommand::Sell => {}
+        }
+
+        self.get_entity_mut(&player)?.player.as_mut()?.docked = Some(docked);
+
+        // a station without services has nothing open to buy from
+        if !spec.offers(docked.service) {
+            return None;
+        }
+
+        match (command, docked.service) {
+            (StationCommand::Buy, Service::Market) => {
+                let commodity = *self
+                    .market_commodities(docked.station)
+                    .get(docked.selection)?;
+                self.buy_commodity(player, docked.station, commodity)
+            }
+            (StationCommand::Sell, Service::Market) => {
+                let commodity = *self
+                    .market_commodities(docked.station)
+                    .get(docked.selection)?;
+                self.sell_commodity(player, docked.station, commodity)
+            }
+            (StationCommand::Buy, Service::Shipyard) => {
+                let offers = self.shipyard_offers(docked.selection);
+                let module = *offers.get(docked.offer % offers.len().max(1))?;
+                self.buy_module(player, docked.selection, module)
+            }
+            (StationCommand::Buy, Service::Repair) => {
+                self.buy_supply(player, *Supply::ALL.get(docked.selection)?)
+            }
+            (StationCommand::Buy, Service::Missions) => {
+                self.accept_contract(player, docked.selection)
+            }
+            _ => None,
+        }
+    }
+
+    /// Takes `cost` credits from the player if they have them.
+    fn pay(&mut self, player: EntityHolder, cost: u32) -> bool {
+        let inventory = match self
+            .get_entity_mut(&player)
+            .and_then(|v| v.inventory.as_mut())
+        {
+            Some(v) => v,
+            None => return false,
+        };
+
+        if inventory.credits < cost {
+            log::info!("not enough credits, {} needed", cost);
+            return false;
+        }
+
+        inventory.credits -= cost;
+
+        true
+    }
+
+    fn trade_reputation(&mut self, station: EntityHolder, credits: u32) {
+        let faction = match self.get_entity(&station).and_then(|v| v.faction) {
+            Some(v) => v,
+            None => return,
+        };
+
+        let current_time = get_time();
+        let (started, gained) = self.traded.entry(station).or_insert((current_time, 0.0));
+        if current_time - *started > TRADE_WINDOW {
+            *started = current_time;
+            *gained = 0.0;
+        }
+
+        let amount = (credits as f32 * TRADE_REPUTATION).min(MAX_TRADE_REPUTATION - *gained);
+        if amount <= 0.0 {
+            return;
+        }
+
+        *gained += amount;
+        self.change_reputation(faction, amount);
+    }
+
+    fn buy_commodity(
+        &mut self,
+        player: EntityHolder,
+        station: EntityHolder,
+        commodity: CommodityId,
+    ) -> Option<()> {
+        let market = self.station_market(station)?;
+        let price = self.unit_buy_price(station, commodity)?;
+
+        let stack = Stack {
+            commodity,
+            amount: 1,
+        };
+        let cargo = self.get_entity(&player)?.cargo.as_ref()?;
+        if cargo.volume() + stack.volume() > cargo.capacity {
+            log::info!("no room in the hold");
+            return None;
+        }
+
+        if !self.pay(player, price) {
+            return None;
+        }
+
+        let player_entity = self.entities.get_mut(player)?;
+        player_entity.cargo.as_mut()?.add(stack);
+        player_entity.update_cargo_mass(&mut self.rigid_body_set.borrow_mut());
+
+        self.economy.trade(market, commodity, 1.0);
+        self.trade_reputation(station, price);
+
+        Some(())
+    }
+
+    fn sell_commodity(
+        &mut self,
+        player: EntityHolder,
+        station: EntityHolder,
+        commodity: CommodityId,
+    ) -> Option<()> {
+        let market = self.station_market(station)?;
+        let price = self.unit_sell_price(station, commodity)?;
+
+        let player_entity = self.entities.get_mut(player)?;
+        player_entity.cargo.as_mut()?.remove(commodity, 1)?;
+        player_entity.inventory.as_mut()?.credits += price;
+        player_entity.update_cargo_mass(&mut self.rigid_body_set.borrow_mut());
+
+        self.economy.trade(market, commodity, -1.0);
+        self.trade_reputation(station, price);
+
+        Some(())
+    }
+
+    fn buy_module(
+        &mut self,
+        player: EntityHolder,
+        slot: usize,
+        module: &'static Module,
+    ) -> Option<()> {
+        if self
+            .installed_module(slot)
+            .is_some_and(|v| std::ptr::eq(v, module))
+        {
+            return None;
+        }
+
+        let cost = self.module_cost(slot, module);
+        if !self.pay(player, cost) {
+            return None;
+        }
+
+        let player_entity = self.entities.get_mut(player)?;
+        let resource = player_entity.resource;
+        player_entity
+            .loadout
+            .as_mut()?
+            .install(resource, slot, module)?;
+        player_entity.apply_loadout();
+        player_entity.update_cargo_mass(&mut self.rigid_body_set.borrow_mut());
+
+        log::info!("installed {} for {} credits", module.name, cost);
+
+        Some(())
+    }
+
+    fn buy_supply(&mut self, player: EntityHolder, supply: Supply) -> Option<()> {
+        let cost = self.supply_cost(supply)?;
+        if cost == 0 || !self.pay(player, cost) {
+            return None;
+        }
+
+        let player_entity = self.get_entity_mut(&player)?;
+        match supply {
+            Supply::Hull => {
+                let health = player_entity.health.as_mut()?;
+                health.hull = health.max_hull;
+            }
+            Supply::Ammo => player_entity.inventory.as_mut()?.ammo += 1,
+            Supply::RepairKit => player_entity.inventory.as_mut()?.repair_kits += 1,
+        }
+
+        Some(())
+    }
+}
@@ -1,31 +1,70 @@
-use std::cell::RefCell;
+use std::{cell::RefCell, collections::HashMap};
 
 use generational_arena::{Arena, Index};
-use macroquad::{
-    camera::Camera2D,
-    prelude::{get_time, vec2},
-};
+use macroquad::prelude::{get_frame_time, get_time, rand, vec2};
 use rapier2d::{
     crossbeam::channel::Receiver,
-    math::Isometry,
+    math::{Isometry, Point, Real, Vector},
     prelude::{
-        ColliderSet, ContactEvent, IntersectionEvent, IslandManager, JointSet, RigidBodySet,
+        ColliderHandle, ColliderSet, ContactEvent, IntersectionEvent, IslandManager, JointSet,
+        NarrowPhase, QueryPipeline, RigidBodySet,
     },
 };
 
 use crate::{
+    economy::simulation::Economy,
     entity::{
+        ai::{Ai, AiLike},
+        animation::{Animation, AnimationLike, AnimationState},
+        beam::BeamLike,
+        boarding::Boardable,
+        cargo::{CargoHold, CargoLike, Stack},
+        debris::DebrisLike,
+        docking::DockingLike,
         drawable::{Drawable, DrawableLike},
         entity::{Entity, EntityBuilder, EntityHolder},
-        physics::{Physics, PhysicsLike},
+        health::{Health, HealthLike},
+        physics::{holder_to_user_data, user_data_to_holder, Physics, PhysicsLike},
+        pickup::PickupLike,
         player::PlayerLike,
         projectile::projectile::ProjectileLike,
+        ram::RamPlating,
+        reactor::ReactorLike,
+        shield::{ShieldLike, Shields},
+        status::StatusLike,
+        thrusters::Thrusters,
+        weapon::{Weapon, WeaponLike},
     },
-    util::{bg::draw_bg, math::random_place_on_map},
-    SHIP,
+    hud::lock::draw_lock_reticles,
+    render::{camera::CameraController, layer::Layer},
+    util::{bg::draw_bg, math::random_place_on_map, screen::wrap_delta},
+    ECONOMY, FACTIONS, MODULES, SHIP,
+};
+
+use super::{
+    contract::Contract,
+    event::GameEvent,
+    reputation::Reputation,
+    world_mutator::{PostInitFn, WorldMutator},
 };
 
-use super::world_mutator::{PostInitFn, WorldMutator};
+const ENEMY_HULL: f32 = 30.0;
+const ENEMY_SHIELDS: f32 = 10.0;
+/// Enemies fire a lot slower than the player with the same weapon.
+pub(super) const ENEMY_COOLDOWN_FACTOR: f64 = 5.0;
+const ENEMY_CRIPPLED_FRACTION: f32 = 0.25;
+/// Hull the player gets back from looting an enemy.
+const ENEMY_SALVAGE: f32 = 40.0;
+/// Most units of a commodity an enemy carries as plunder.
+const ENEMY_PLUNDER: u32 = 8;
+/// One in this many enemies is a pirate rammer.
+const PIRATE_CHANCE: u32 = 6;
+const PIRATE_FACTION: &str = "raiders";
+const ENEMY_ENGINE: &str = "engine_standard";
+const PIRATE_ENGINE: &str = "engine_racing";
+/// Convoy guards break off to chase the player only this close to the convoy.
+const GUARD_RANGE: Real = 1200.0;
+
 pub struct World {
     pub entities: Arena<Entity>,
     pub player: Option<EntityHolder>,
@@ -33,6 +72,21 @@ pub struct World {
     pub collider_set: RefCell<ColliderSet>,
     pub island_manager: RefCell<IslandManager>,
     pub joint_set: RefCell<JointSet>,
+    pub narrow_phase: RefCell<NarrowPhase>,
+    pub query_pipeline: RefCell<QueryPipeline>,
+
+    pub events: Vec<GameEvent>,
+    pub reputation: Reputation,
+    pub economy: Economy,
+    /// Accepted by the player and not delivered yet.
+    pub contracts: Vec<Contract>,
+    /// What the station the player is docked at has on offer.
+    pub offers: Vec<Contract>,
+    /// When the last convoy set out.
+    pub last_convoy: f64,
+    /// Per station, when the current trade reputation window started and
+    /// what the player gained in it.
+    pub traded: HashMap<EntityHolder, (f64, f32)>,
 }
 
 fn add_entity_property(
@@ -42,15 +96,27 @@ fn add_entity_property(
     collider_set: &mut ColliderSet,
 ) -> Option<()> {
     let rigid_body = entity.resource.info.rigid_body.as_ref()?.clone();
-    let collider = entity.resource.info.collider.as_ref()?.clone();
+    let mut collider = match entity.debris {
+        Some(_) => entity.debris_collider()?,
+        None => entity.resource.info.collider.as_ref()?.clone(),
+    };
+    collider.user_data = holder_to_user_data(entity_holder);
 
     let rigid_body_handle = rigid_body_set.insert(rigid_body);
     let collider_handle =
         collider_set.insert_with_parent(collider, rigid_body_handle, rigid_body_set);
 
+    let dock_handle = entity.resource.info.dock.clone().map(|mut dock| {
+        dock.user_data = holder_to_user_data(entity_holder);
+        collider_set.insert_with_parent(dock, rigid_body_handle, rigid_body_set)
+    });
+
     entity.physics = Some(Physics {
         rigid_body_handle,
         collider_handle,
+        dock_handle,
+        previous_velocity: Vector::zeros(),
+        base_mass: *rigid_body_set[rigid_body_handle].mass_properties(),
     });
 
     entity.entity_holder = Some(entity_holder);
@@ -59,13 +125,13 @@ fn add_entity_property(
 }
 
 impl World {
-    fn handle_mutator(&mut self, world_mutator: WorldMutator) -> Option<EntityHolder> {
+    pub(super) fn handle_mutator(&mut self, world_mutator: WorldMutator) -> Option<EntityHolder> {
         match world_mutator {
             WorldMutator::Remove(entity_holder) => {
                 self.remove_entity(entity_holder);
                 None
             }
-            WorldMutator::Add(entity, post_init) => self.add_entity(entity, post_init),
+            WorldMutator::Add(entity, post_init) => self.add_entity(*entity, post_init),
         }
     }
 
@@ -77,12 +143,33 @@ impl World {
         self.entities.get(*holder)
     }
 
+    /// Removes the entity with its rigid body. Destroyed ships break apart
+    /// into debris, drop loot and push everything around them away.
     pub fn remove_entity(&mut self, entity_holder: EntityHolder) -> Entity {
         let entity = self
             .entities
             .remove(entity_holder)
             .expect("entity was never in the arena");
 
+        let debris = if entity.is_destroyed() {
+            let rigid_body_set = &*self.rigid_body_set.borrow();
+            let loot = entity.drop_loot(rigid_body_set).into_iter().flatten();
+            let cargo = entity.spill_cargo(rigid_body_set).into_iter().flatten();
+            let pieces = entity.break_apart(rigid_body_set).into_iter().flatten();
+
+            Some(pieces.chain(loot).chain(cargo).collect::<Vec<_>>())
+        } else {
+            None
+        };
+
+        if debris.is_some() {
+            self.explode(&entity);
+        }
+
+        if entity.merchant.is_some() {
+            self.disband_convoy(entity_holder);
+        }
+
         if let Some(handle) = entity.physics.and_then(|v| Some(v.rigid_body_handle)) {
             let rigid_body_set = &mut *self.rigid_body_set.borrow_mut();
             let collider_set = &mut *self.collider_set.borrow_mut();
@@ -92,6 +179,10 @@ impl World {
             rigid_body_set.remove(handle, island_manager, collider_set, joint_set);
         }
 
+        debris.into_iter().flatten().for_each(|v| {
+            self.handle_mutator(v);
+        });
+
         return entity;
     }
 
@@ -134,85 +225,333 @@ impl World {
         Some(entity_holder)
     }
 
+    pub fn get_holder_by_collider(&self, handle: ColliderHandle) -> Option<EntityHolder> {
+        let collider_set = self.collider_set.borrow();
+        let holder = user_data_to_holder(collider_set.get(handle)?.user_data);
+
+        self.entities.contains(holder).then_some(holder)
+    }
+
+    fn contact(
+        &mut self,
+        contact_event: ContactEvent,
+        to_remove: &mut Vec<EntityHolder>,
+    ) -> Option<()> {
+        let (collider_a, collider_b) = match contact_event {
+            ContactEvent::Started(a, b) => (a, b),
+            ContactEvent::Stopped(_, _) => return None,
+        };
+
+        let point = self.contact_point(collider_a, collider_b);
+
+        let a = self.get_holder_by_collider(collider_a)?;
+        let b = self.get_holder_by_collider(collider_b)?;
+
+        let is_projectile = |holder| {
+            self.get_entity(&holder)
+                .is_some_and(|v| v.projectile.is_some())
+        };
+
+        if is_projectile(a) || is_projectile(b) {
+            self.projectile_hit(a, b, point, to_remove);
+            self.projectile_hit(b, a, point, to_remove);
+
+            return Some(());
+        }
+
+        self.collision_damage((collider_a, a), (collider_b, b), point, to_remove)
+    }
+
     pub fn set_player(&mut self, world_mutator: WorldMutator) -> Option<()> {
         self.player = self.handle_mutator(world_mutator);
 
         Some(())
     }
 
-    fn mouse(&mut self, player: &Index, camera: &mut Camera2D) -> Option<()> {
+    fn mouse(&mut self, player: &Index, camera: &CameraController) -> Option<()> {
         let player_entity = self.get_entity_mut(&player)?;
 
         let mouse_info = player_entity.mouse_info_mut()?;
-        mouse_info.from_mouse(&camera);
-        mouse_info.draw_cursor();
+        mouse_info.from_mouse(&camera.camera);
 
         Some(())
     }
 
-    fn camera(&self, player: &Index, camera: &mut Camera2D) -> Option<()> {
+    fn camera(&self, player: &Index, camera: &mut CameraController) -> Option<()> {
         let player_entity = self.get_entity(&player)?;
 
         let rigid_body_set = &mut self.rigid_body_set.borrow();
 
-        let pos = *player_entity.pos(rigid_body_set)?;
-        camera.target = vec2(pos.x, pos.y);
+        let pos = player_entity.pos(rigid_body_set)?;
+        let velocity = player_entity.velocity(rigid_body_set)?;
+        let mouse_pos = player_entity.mouse_info()?.pos;
+
+        camera.update(
+            vec2(pos.x, pos.y),
+            vec2(velocity.x, velocity.y),
+            mouse_pos,
+            get_frame_time(),
+        );
 
         Some(())
     }
 
     fn input(&mut self, player: &Index) -> Option<()> {
-        let player_entity = self.get_entity(&player)?;
+        let player_entity = self.entities.get_mut(*player)?;
         let rigid_body_set = &mut *self.rigid_body_set.borrow_mut();
 
         let rigid_body = player_entity.get_rigid_body_mut(rigid_body_set)?;
 
+        if player_entity.is_disabled() || player_entity.is_docked() {
+            return None;
+        }
+
         player_entity.update_input(rigid_body);
 
         Some(())
     }
 
-    fn fire(&mut self, player: &Index) -> Option<WorldMutator> {
+    fn fire(&mut self, player: &Index) -> Option<Vec<WorldMutator>> {
         let player_entity = self.get_entity_mut(player)?;
 
-        player_entity.update_fire(&player)
+        if player_entity.is_docked() {
+            return None;
+        }
+
+        player_entity.update_fire()
+    }
+
+    fn jettison(&mut self, player: &Index) -> Option<Vec<WorldMutator>> {
+        let player_entity = self.entities.get_mut(*player)?;
+        let rigid_body_set = &mut *self.rigid_body_set.borrow_mut();
+
+        if player_entity.is_docked() {
+            return None;
+        }
+
+        Some(vec![player_entity.update_jettison(rigid_body_set)?])
+    }
+
+    /// Hostile or provoked ships attack the player, escorts fight or follow,
+    /// convoys fly their route. In the peace zone only merchants move.
+    fn ai(&mut self, player: &Index, current_time: f64) -> Option<Vec<WorldMutator>> {
+        let dt = get_frame_time();
+        let peace_zone = self.peace_zone();
+        let rigid_body_set = &mut *self.rigid_body_set.borrow_mut();
+        let target = self.entities.get(*player)?.pos(rigid_body_set)?;
+
+        let reputation = &self.reputation;
+        let is_hostile = |v: &Entity| {
+            v.ai.is_some_and(|v| v.provoked) || v.faction.is_none_or(|f| reputation.is_hostile(f))
+        };
+
+        let hostiles = self
+            .entities
+            .iter()
+            .filter(|(_, v)| v.ai.is_some() && v.escort.is_none() && !v.is_crippled())
+            .filter(|(_, v)| is_hostile(v))
+            .filter_map(|(_, v)| v.pos(rigid_body_set))
+            .collect::<Vec<_>>();
+
+        let leaders = self
+            .entities
+            .iter()
+            .filter_map(|(_, v)| v.escort)
+            .filter_map(|v| Some((v.leader, self.entities.get(v.leader)?.pos(rigid_body_set)?)))
+            .collect::<HashMap<_, _>>();
+
+        let stations = self
+            .entities
+            .iter()
+            .filter(|(_, v)| v.station.is_some())
+            .filter_map(|(holder, v)| Some((holder, v.pos(rigid_body_set)?)))
+            .collect::<HashMap<_, _>>();
+
+        let convoys = self
+            .entities
+            .iter()
+            .filter_map(|(holder, v)| {
+                Some((holder, (v.pos(rigid_body_set)?, v.merchant?.attacked)))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut mutators = vec![];
+
+        for (_, entity) in self.entities.iter_mut() {
+            let ai = match entity.ai {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let rigid_body = match entity.get_rigid_body_mut(rigid_body_set) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let pos = Point::from(*rigid_body.translation());
+            let offline = entity.is_disabled() || entity.is_crippled();
+            if entity.is_crippled() {
+                entity.set_animation_state(AnimationState::Damaged, current_time);
+            }
+
+            let trigger = if offline {
+                false
+            } else if let Some(merchant) = entity.merchant {
+                if let Some(destination) = stations.get(&merchant.destination) {
+                    entity.follow(*destination, rigid_body, dt);
+                }
+                false
+            } else if Self::is_peaceful(peace_zone, pos) {
+                false
+            } else if let Some(escort) = entity.escort {
+                let hostile = hostiles
+                    .iter()
+                    .map(|v| (wrap_delta(pos, *v).norm(), *v))
+                    .filter(|v| v.0 < ai.range)
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+
+                match (hostile, leaders.get(&escort.leader)) {
+                    (Some((_, hostile)), _) => {
+                        entity.update_ai(hostile, rigid_body, dt).unwrap_or(false)
+                    }
+                    (None, Some(leader)) => {
+                        entity.follow(*leader, rigid_body, dt);
+                        false
+                    }
+                    (None, None) => false,
+                }
+            } else if let Some(guard) = entity.guard {
+                match guard.leader.and_then(|v| convoys.get(&v)) {
+                    Some((leader, attacked))
+                        if *attacked && wrap_delta(*leader, target).norm() < GUARD_RANGE =>
+                    {
+                        entity.update_ai(target, rigid_body, dt).unwrap_or(false)
+                    }
+                    Some((leader, _)) => {
+                        entity.follow(*leader, rigid_body, dt);
+                        false
+                    }
+                    None => {
+                        if let Some(home) = stations.get(&guard.home) {
+                            entity.follow(*home, rigid_body, dt);
+                        }
+                        false
+                    }
+                }
+            } else if is_hostile(entity) {
+                entity.update_ai(target, rigid_body, dt).unwrap_or(false)
+            } else {
+                false
+            };
+
+            mutators.extend(
+                entity
+                    .update_weapons(trigger, current_time)
+                    .into_iter()
+                    .flatten(),
+            );
+        }
+
+        Some(mutators)
     }
 
     fn spawn_enemy(&mut self) -> Option<Vec<WorldMutator>> {
         let p = random_place_on_map();
 
-        let drawable = Drawable::from_resource(&SHIP)?;
-
-        Some(vec![EntityBuilder::new(&SHIP)
+        // pirates with reinforced bows fly straight into their target
+        let rammer = rand::gen_range(0, PIRATE_CHANCE) == 0;
+        let faction = if rammer {
+            FACTIONS.get(PIRATE_FACTION)?
+        } else {
+            FACTIONS.roll(rand::rand())?
+        };
+
+        let engine = if rammer { PIRATE_ENGINE } else { ENEMY_ENGINE };
+        let thrusters = MODULES.get(engine).and_then(Thrusters::from_module)?;
+
+        let drawable = Drawable::from_resource(&SHIP)?.tint(faction.color);
+        let weapon = match rand::gen_range(0, 10) {
+            0 | 1 => Weapon::scatter(0),
+            2 => Weapon::missile_launcher(0),
+            3 => Weapon::ion_cannon(0),
+            4 => Weapon::incendiary(0),
+            5 => Weapon::disruptor(0),
+            6 => Weapon::snare(0),
+            _ => Weapon::blaster(0),
+        };
+        let weapon = Weapon {
+            cooldown: weapon.cooldown * ENEMY_COOLDOWN_FACTOR,
+            ..weapon
+        };
+
+        let mut builder = EntityBuilder::new(&SHIP);
+        builder
             .drawable(drawable)
-            .build_mutator(Box::new(move |entity, rigid_body_set| {
+            .animation(Animation::default())
+            .health(
+                Health::new(ENEMY_HULL)
+                    .shields(Shields::new(ENEMY_SHIELDS, 4.0, 3.0))
+                    .crippled_below(ENEMY_CRIPPLED_FRACTION),
+            )
+            .boardable(Boardable {
+                salvage: ENEMY_SALVAGE,
+            })
+            .thrusters(thrusters)
+            .weapon(weapon)
+            .faction(faction);
+
+        let commodities = ECONOMY.commodities.len();
+        if commodities > 0 {
+            let plunder = Stack {
+                commodity: rand::gen_range(0, commodities),
+                amount: rand::gen_range(1, ENEMY_PLUNDER + 1),
+            };
+            builder.cargo(CargoHold {
+                capacity: plunder.volume(),
+                stacks: vec![plunder],
+            });
+        }
+
+        if rammer {
+            builder.ai(Ai::rammer()).ram_plating(RamPlating::default());
+        } else {
+            builder.ai(Ai::default());
+        }
+
+        Some(vec![builder.build_mutator(Box::new(
+            move |entity, rigid_body_set| {
                 entity
                     .get_rigid_body_mut(rigid_body_set)?
                     .set_position(Isometry::translation(p.0, p.1), false);
 
                 None
-            }))])
+            },
+        ))])
     }
 
     pub fn update(
         &mut self,
         contact_recv: &Receiver<ContactEvent>,
         intersection_recv: &Receiver<IntersectionEvent>,
-        camera: &mut Camera2D,
+        camera: &mut CameraController,
     ) -> Option<()> {
         let current_time = get_time();
 
-        draw_bg();
-
         let player = self.player?;
 
         self.camera(&player, camera);
         self.mouse(&player, camera);
         self.input(&player);
 
-        self.fire(&player).and_then(|v| {
+        self.update_locks(current_time);
+
+        let mutators = [
+            self.fire(&player),
+            self.jettison(&player),
+            self.ai(&player, current_time),
+        ];
+        mutators.into_iter().flatten().flatten().for_each(|v| {
             self.handle_mutator(v);
-            Some(())
         });
 
         if current_time % 1.0 <= 0.1 {
@@ -225,42 +564,125 @@ impl World {
         }
 
         let mut to_remove: Vec<EntityHolder> = vec![];
+        let mut burns = vec![];
         {
             let rigid_body_set = &mut *self.rigid_body_set.borrow_mut();
 
-            for (_, entity) in self.entities.iter_mut() {
+            for (holder, entity) in self.entities.iter_mut() {
                 entity.update_entity_position(rigid_body_set);
+                entity.update_reactor(get_frame_time());
+                entity.update_health(current_time, get_frame_time());
 
-                if let Some(WorldMutator::Remove(entity_holder)) =
-                    entity.update_projectile(current_time)
+                if let Some(burning) =
+                    entity.update_status(current_time, get_frame_time(), rigid_body_set)
                 {
-                    to_remove.push(entity_holder);
+                    burns.extend(
+                        burning
+                            .into_iter()
+                            .map(|(dps, source)| (holder, dps, source)),
+                    );
                 }
+
+                let expired = [
+                    entity.update_projectile(current_time),
+                    entity.update_debris(current_time),
+                    entity.update_pickup(current_time),
+                ];
+                expired.into_iter().flatten().for_each(|v| {
+                    if let WorldMutator::Remove(entity_holder) = v {
+                        to_remove.push(entity_holder);
+                    }
+                });
             }
         }
 
+        self.apply_burns(burns, &mut to_remove);
+        self.update_missiles(&mut to_remove);
+        self.update_beams(current_time, &mut to_remove);
+        self.update_boarding(player, current_time, &mut to_remove);
+        self.update_docking(player, current_time);
+        self.update_convoys(current_time, &mut to_remove);
+        self.expire_contracts(current_time);
+        self.update_magnet(player);
+        self.collect(player, &mut to_remove);
+        self.economy
+            .tick(get_frame_time(), || rand::gen_range(-1.0, 1.0));
+
         while let Ok(intersection_event) = intersection_recv.try_recv() {
             log::debug!("Received intersection event: {:?}", intersection_event);
+
+            self.dock_zone(player, intersection_event);
         }
 
-        /*
         while let Ok(contact_event) = contact_recv.try_recv() {
             log::debug!("Received contact event: {:?}", contact_event);
+
+            self.contact(contact_event, &mut to_remove);
         }
-        */
 
+        // velocities going into the next physics step, for the damage of its contacts
+        {
+            let rigid_body_set = &*self.rigid_body_set.borrow();
+
+            self.entities.iter_mut().for_each(|(_, entity)| {
+                entity.remember_velocity(rigid_body_set);
+            });
+        }
+
+        to_remove.sort();
+        to_remove.dedup();
         to_remove.into_iter().for_each(|index| {
             self.remove_entity(index);
         });
 
-        let rigid_body_set = &mut *self.rigid_body_set.borrow_mut();
-        let collider_set = &*self.collider_set.borrow();
+        self.draw(&player);
+
+        Some(())
+    }
 
-        self.entities.iter_mut().for_each(|(_, entity)| {
-            entity.draw(&rigid_body_set, &collider_set);
+    /// Draws everything layer by layer. Within a layer, entities keep their
+    /// arena order.
+    fn draw(&self, player: &Index) {
+        let rigid_body_set = &*self.rigid_body_set.borrow();
+
+        let mut buckets: HashMap<Layer, Vec<&Entity>> = HashMap::new();
+        self.entities.iter().for_each(|(_, entity)| {
+            if let Some(drawable) = entity.drawable {
+                buckets.entry(drawable.layer).or_default().push(entity);
+            }
         });
 
-        Some(())
+        Layer::ALL.iter().for_each(|layer| {
+            if *layer == Layer::Background {
+                draw_bg();
+            }
+
+            buckets.get(layer).into_iter().flatten().for_each(|entity| {
+                entity.draw(rigid_body_set);
+            });
+
+            if *layer == Layer::Projectiles {
+                self.entities.iter().for_each(|(_, entity)| {
+                    entity.draw_beams(rigid_body_set);
+                });
+            }
+
+            if *layer == Layer::Effects {
+                self.entities.iter().for_each(|(_, entity)| {
+                    entity.draw_shield(rigid_body_set, get_time());
+                });
+            }
+
+            if *layer == Layer::WorldUi {
+                draw_lock_reticles(self, rigid_body_set);
+            }
+
+            if *layer == Layer::Cursor {
+                if let Some(mouse_info) = self.get_entity(player).and_then(|v| v.mouse_info()) {
+                    mouse_info.draw_cursor();
+                }
+            }
+        });
     }
 }
 
@@ -273,6 +695,15 @@ impl Default for World {
             collider_set: RefCell::new(ColliderSet::new()),
             island_manager: RefCell::new(IslandManager::new()),
             joint_set: RefCell::new(JointSet::new()),
+            narrow_phase: RefCell::new(NarrowPhase::new()),
+            query_pipeline: RefCell::new(QueryPipeline::new()),
+            events: vec![],
+            reputation: Reputation::new(&FACTIONS),
+            economy: ECONOMY.clone(),
+            contracts: vec![],
+            offers: vec![],
+            last_convoy: 0.0,
+            traded: HashMap::new(),
         }
     }
 }
@@ -8,5 +8,5 @@ pub type PostInitFn =
 #[must_use]
 pub enum WorldMutator {
     Remove(EntityHolder),
-    Add(Entity, PostInitFn),
+    Add(Box<Entity>, PostInitFn),
 }